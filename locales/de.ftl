@@ -0,0 +1,312 @@
+# Conversion embeds
+listen-by = Hör dir { $title } von { $artist } auf Cider an
+listen = Hör dir { $title } auf Cider an
+tune-into = Schalte { $title } auf Cider ein
+shared-by = Geteilt von { $name }
+play-in-cider = In Cider abspielen
+view-in-cider = In Cider ansehen
+maintenance = Cidar wird gerade gewartet, Links werden vorerst nicht umgewandelt
+lyrics-button = Songtext
+lyrics-page = Seite { $page } von { $pages } • Songtext von Apple Music
+alternates-placeholder = Andere Versionen…
+similar-button = Ähnliches
+open-in-cider = In Cider öffnen
+similar-title = Mehr davon
+charts-page = Seite { $page } von { $pages } • Charts von Apple Music
+charts-title = Charts
+title-by = { $title } von { $artist }
+unknown-artist = Unbekannter Künstler
+nothing-shared = Noch nichts geteilt
+paging-previous = Zurück
+paging-next = Weiter
+
+# Song details
+field-genre = Genre
+field-composer = Komponist
+field-track = Titel
+field-rating = Altersfreigabe
+field-audio = Audio
+field-label = Label
+field-copyright = Copyright
+field-top-songs = Beliebteste Songs
+field-latest-release = Neueste Veröffentlichung
+field-playlists = Playlists
+field-releases = Neueste Veröffentlichungen
+field-featured = Empfohlen
+track-number = { $track } auf Disc { $disc }
+rating-explicit = 🅴 Explizit
+rating-clean = Jugendfrei
+
+# Medientypen
+media-song = Song
+media-album = Album
+media-playlist = Playlist
+media-music-video = Musikvideo
+media-station = Sender
+media-artist = Künstler
+media-curator = Kurator
+media-apple-curator = Apple Kurator
+media-radio-show = Radiosendung
+media-record-label = Plattenlabel
+media-editorial = Redaktion
+media-social-profile = Profil
+
+# Durations and dates
+released = Erschienen
+duration-days = { $count ->
+    [one] { $count } Tag
+   *[other] { $count } Tage
+}
+duration-hours = { $count } Std.
+duration-minutes = { $count } Min.
+duration-seconds = { $count } Sek.
+
+# Errors
+convert-invalid-input = Kein Link angegeben
+convert-invalid-input-hint = Füge den Link, den du umwandeln willst, in die Option `link` ein.
+convert-invalid-content = Das ist kein Link
+convert-invalid-content-hint = Verwende den vollständigen Link, z.B. https://open.spotify.com/track/...
+convert-failed = Konnte nicht in einen Apple Music Link umgewandelt werden
+convert-failed-hint = song.link hat das nicht auf Apple Music gefunden, vielleicht ist es dort nicht verfügbar.
+convert-request = Anfrage fehlgeschlagen
+convert-request-hint = song.link hat nicht geantwortet, versuch es in einer Minute nochmal.
+lyrics-invalid-input = Kein Song angegeben
+lyrics-invalid-input-hint = Füge einen Apple Music Link ein oder gib ein, wonach gesucht werden soll.
+lyrics-not-found = Song nicht gefunden
+lyrics-not-found-hint = Verwende einen Apple Music Link oder such zusätzlich mit dem Namen des Künstlers.
+lyrics-unavailable = Kein Songtext verfügbar
+lyrics-unavailable-hint = Apple Music hat für diesen Song noch keinen Songtext.
+lyrics-request = Anfrage fehlgeschlagen
+lyrics-request-hint = Apple Music hat nicht geantwortet, versuch es in einer Minute nochmal.
+alternate-not-found = Version nicht gefunden
+alternate-not-found-hint = Apple Music hat diese Version nicht mehr, versuch eine andere.
+similar-not-found = Nichts Ähnliches gefunden
+similar-not-found-hint = Apple Music hat dafür noch keine Empfehlungen.
+similar-request = Anfrage fehlgeschlagen
+similar-request-hint = Apple Music hat nicht geantwortet, versuch es in einer Minute nochmal.
+follow-not-found = Künstler nicht gefunden
+follow-not-found-hint = Prüfe die Schreibweise oder wähle einen der Vorschläge.
+follow-request = Anfrage fehlgeschlagen
+follow-request-hint = Apple Music hat nicht geantwortet, versuch es in einer Minute nochmal.
+follow-invalid-storefront = Unbekannter Store
+follow-invalid-storefront-hint = Nutze einen zweistelligen Ländercode wie us, gb oder de.
+history-invalid-date = Kein Datum
+history-invalid-date-hint = Daten sehen so aus: 2023-07-14, das Jahr zuerst.
+charts-invalid-storefront = Unbekannter Store
+charts-invalid-storefront-hint = Nutze einen zweistelligen Ländercode wie us, gb oder de.
+charts-genre-not-found = Genre nicht gefunden
+charts-genre-not-found-hint = Wähle eines der vorgeschlagenen Genres für diesen Store.
+charts-empty = Keine Charts verfügbar
+charts-empty-hint = Apple Music hat diese Charts für diesen Store nicht, versuch einen anderen Typ oder ein anderes Genre.
+charts-request = Anfrage fehlgeschlagen
+charts-request-hint = Apple Music hat nicht geantwortet, versuch es in einer Minute nochmal.
+storage-database = Datenbankanfrage fehlgeschlagen
+storage-database-hint = Die Datenbank ist gerade nicht erreichbar, versuch es gleich nochmal.
+storage-internal = Gespeicherte Daten konnten nicht gelesen werden
+storage-internal-hint = Bei uns ist etwas schiefgelaufen, sag dem Cider Team Bescheid, wenn das öfter passiert.
+
+# /about
+about-version = Version
+about-authors = Autor(en)
+about-build-time = Build-Zeit
+about-commit = Commit-Hash
+about-rust = Rust-Version
+
+# /stats
+stats-title = Umwandlungen
+stats-total = Gesamt: { $total }
+stats-total-guild = Gesamt: { $total } (dieser Server: { $guild })
+stats-recent = Letzte 24 Stunden: { $day } • 7 Tage: { $week } • 30 Tage: { $month }
+stats-by-type = Nach Medientyp (30 Tage)
+stats-by-platform = Nach Plattform (30 Tage)
+stats-top-artists = Meistgeteilte Künstler (30 Tage)
+stats-top-albums = Meistgeteilte Alben (30 Tage)
+stats-uptime = Laufzeit: { $uptime }
+
+# /leaderboard
+leaderboard-guild-only = Bestenlisten gibt es nur auf Servern
+leaderboard-week = Bestenliste dieser Woche
+leaderboard-month = Bestenliste dieses Monats
+leaderboard-all = Bestenliste aller Zeiten
+leaderboard-sharers = Fleißigste Teiler
+leaderboard-tracks = Top Titel
+leaderboard-albums = Top Alben
+leaderboard-artists = Top Künstler
+
+# /history
+history-guild-only = Den Verlauf gibt es nur auf Servern
+history-none = Keine geteilte Musik passt zu diesem Filter
+history-title = Verlauf
+history-page = Seite { $page } von { $pages }
+history-entry = { $title } ({ $kind }) geteilt von { $name }
+history-jump = ansehen
+
+# /config
+config-guild-only = Einstellungen gibt es nur auf Servern
+config-pick = Wähle eine Einstellung aus
+config-digest-enabled = Der Wochenrückblick wird in { $channel } gepostet
+config-digest-disabled = Der Wochenrückblick ist jetzt deaktiviert
+config-language-set = Cidar antwortet auf diesem Server jetzt auf { $language }
+config-language-reset = Cidar folgt jetzt der Sprache des Servers
+config-field-shown = Embeds zeigen jetzt { $field }
+config-field-hidden = Embeds zeigen { $field } nicht mehr an
+config-links-buttons = Embeds zeigen { $buttons }
+config-links-none = Embeds zeigen keine Cider Buttons mehr an
+config-links-scheme-web = Links öffnen über { $base }
+config-links-scheme-cider = Embeds bekommen zusätzlich cider:// Links, die direkt die App öffnen
+
+# /follow
+follow-guild-only = Künstlern folgen geht nur auf Servern
+follow-added = Neue Veröffentlichungen von { $artist } werden in { $channel } gepostet
+follow-removed = { $artist } wird nicht mehr gefolgt
+follow-not-following = Dieser Server folgt { $artist } nicht
+follow-none = Dieser Server folgt noch keinen Künstlern
+follow-list = Gefolgte Künstler
+follow-limit = Dieser Server folgt schon { $count } Künstlern, entferne zuerst einen
+follow-list-entry = - { $artist } in { $channel }
+follow-pick = Wähle aus, was du tun möchtest
+
+# /admin
+admin-owner-only = Das darf nur der Besitzer des Bots
+admin-pick = Wähle einen Admin Befehl
+admin-token-refreshed = Entwickler-Token erneuert
+admin-token-failed = Das Entwickler-Token konnte nicht erneuert werden, das bisherige bleibt
+admin-cache-cleared = { $count } zwischengespeicherte Katalogantworten gelöscht, die anderen Shards folgen innerhalb von { $seconds } Sekunden
+admin-cache-cleared-here = { $count } zwischengespeicherte Katalogantworten nur auf diesem Shard gelöscht, der Speicher ist nicht erreichbar
+admin-cache-catalog = Katalog-Cache
+admin-cache-entries = Einträge: { $entries } ({ $expired } abgelaufen)
+admin-cache-hits = Treffer: { $hits } • Fehlschläge: { $misses }
+admin-cache-discord = Discord-Cache
+admin-cache-discord-counts = Server: { $guilds } • Kanäle: { $channels } • Nutzer: { $users }
+admin-reloaded = Konfiguration neu geladen, { $templates } Status-Vorlagen und { $admins } Admins
+admin-health-ready = Bereit
+admin-health-shards = Shards
+admin-health-token = Token-Alter
+admin-health-upstreams = Letzte erfolgreiche Anfrage
+admin-yes = ja
+admin-no = nein
+admin-shard-up = { $shard } läuft
+admin-shard-down = { $shard } ausgefallen
+admin-token-age = { $minutes } Minuten
+admin-token-never = nie erneuert
+admin-none-yet = Noch keine
+admin-maintenance-on = Wartungsmodus an, die automatische Umwandlung pausiert auf allen Shards innerhalb von { $seconds } Sekunden
+admin-maintenance-off = Wartungsmodus aus, die automatische Umwandlung läuft auf allen Shards innerhalb von { $seconds } Sekunden wieder
+admin-maintenance-here = Der Wartungsmodus wurde nur auf diesem Shard geändert, der Speicher ist nicht erreichbar
+admin-commands-registered = Befehle registriert
+admin-commands-failed = Befehle konnten nicht registriert werden: { $error }
+
+# Release announcements
+new-release = Neue Musik von { $artist }
+new-release-footer = Gepostet, weil dieser Server dem Künstler folgt
+
+# Weekly digest
+digest-title = Euer Wochenrückblick
+digest-top-sharers = Am meisten geteilt von
+digest-footer = { $count ->
+    [one] { $count } Link diese Woche geteilt
+   *[other] { $count } Links diese Woche geteilt
+}
+
+# Slash commands
+command-about-description = Informationen über Cidar
+command-convert-name = umwandeln
+command-convert-description = Wandelt einen Link in sein Apple Music Gegenstück um
+command-convert-link-description = Link zum Medium
+command-stats-name = statistik
+command-stats-description = Zeigt Statistiken zu Umwandlungen
+command-leaderboard-name = bestenliste
+command-leaderboard-description = Wer teilt die meiste Musik und was wird am meisten geteilt
+command-leaderboard-window-name = zeitraum
+command-leaderboard-window-description = Zeitraum für die Rangliste
+command-leaderboard-window-choice-week = Wöchentlich
+command-leaderboard-window-choice-month = Monatlich
+command-leaderboard-window-choice-all = Gesamt
+command-history-name = verlauf
+command-history-description = Durchsuche die auf diesem Server geteilte Musik
+command-history-user-name = mitglied
+command-history-user-description = Nur Musik, die dieses Mitglied geteilt hat
+command-history-artist-name = künstler
+command-history-artist-description = Nur Musik von diesem Künstler
+command-history-type-name = typ
+command-history-type-description = Nur diese Art von Medium
+command-history-type-choice-so = { media-song }
+command-history-type-choice-al = { media-album }
+command-history-type-choice-pl = { media-playlist }
+command-history-type-choice-mv = { media-music-video }
+command-history-type-choice-st = { media-station }
+command-history-type-choice-ar = { media-artist }
+command-history-type-choice-cu = { media-curator }
+command-history-type-choice-ac = { media-apple-curator }
+command-history-type-choice-rs = { media-radio-show }
+command-history-type-choice-rl = { media-record-label }
+command-history-type-choice-ed = { media-editorial }
+command-history-type-choice-sp = { media-social-profile }
+command-history-date-name = datum
+command-history-date-description = Nur Musik von diesem Tag, z.B. 2023-07-14
+command-config-description = Lege fest, wie sich Cidar auf diesem Server verhält
+command-config-digest-name = rückblick
+command-config-digest-description = Poste einen wöchentlichen Rückblick, leer lassen zum Deaktivieren
+command-config-digest-channel-name = kanal
+command-config-digest-channel-description = Kanal für den Rückblick
+command-config-language-name = sprache
+command-config-language-description = Sprache für Cidars Nachrichten, leer lassen für die Serversprache
+command-config-language-locale-name = sprache
+command-config-language-locale-description = Die Sprache, die Cidar verwenden soll
+command-config-fields-name = details
+command-config-fields-description = Details auf Embeds ein- oder ausblenden
+command-config-fields-field-name = detail
+command-config-fields-field-description = Das Detail, das geändert werden soll
+command-config-fields-field-choice-field-genre = { field-genre }
+command-config-fields-field-choice-field-composer = { field-composer }
+command-config-fields-field-choice-field-track = { field-track }
+command-config-fields-field-choice-field-rating = { field-rating }
+command-config-fields-field-choice-field-audio = { field-audio }
+command-config-fields-field-choice-field-label = { field-label }
+command-config-fields-field-choice-field-copyright = { field-copyright }
+command-config-fields-field-choice-field-top-songs = { field-top-songs }
+command-config-fields-field-choice-field-latest-release = { field-latest-release }
+command-config-fields-field-choice-field-playlists = { field-playlists }
+command-config-fields-field-choice-field-releases = { field-releases }
+command-config-fields-field-choice-field-featured = { field-featured }
+command-config-fields-shown-name = anzeigen
+command-config-fields-shown-description = Ob es angezeigt werden soll
+command-config-links-name = links
+command-config-links-description = Lege die Cider Buttons auf Embeds fest und wie ihre Links öffnen
+command-config-links-play-name = abspielen
+command-config-links-play-description = Den In Cider abspielen Button anzeigen
+command-config-links-view-name = ansehen
+command-config-links-view-description = Den In Cider ansehen Button anzeigen
+command-config-links-scheme-name = schema
+command-config-links-scheme-description = Zusätzlich cider:// Links, die direkt die App öffnen
+command-config-links-scheme-choice-web = Web-Weiterleitung
+command-config-links-scheme-choice-cider = Cider App
+command-lyrics-name = songtext
+command-lyrics-description = Zeigt den Songtext eines Songs
+command-lyrics-song-description = Ein Apple Music Link oder wonach gesucht werden soll
+command-follow-name = folgen
+command-follow-description = Neue Veröffentlichungen von Künstlern auf diesem Server posten
+command-follow-artist-name = künstler
+command-follow-artist-description = Einem Künstler folgen
+command-follow-artist-name-name = name
+command-follow-artist-name-description = Der Künstler, dem gefolgt werden soll
+command-follow-artist-channel-name = kanal
+command-follow-artist-channel-description = Kanal für neue Veröffentlichungen, leer lassen für diesen
+command-follow-artist-storefront-name = store
+command-follow-artist-storefront-description = Ländercode des Stores, in dem gefolgt wird, us wenn leer
+command-follow-remove-name = entfernen
+command-follow-remove-description = Einem Künstler nicht mehr folgen
+command-follow-remove-name-name = name
+command-follow-remove-name-description = Der Künstler, dem nicht mehr gefolgt werden soll
+command-follow-list-name = liste
+command-follow-list-description = Zeigt die Künstler, denen dieser Server folgt
+command-charts-description = Zeigt die Apple Music Charts
+command-charts-storefront-name = store
+command-charts-storefront-description = Ländercode der Charts, z.B. us oder de
+command-charts-type-name = typ
+command-charts-type-description = Was gerankt werden soll, standardmäßig Songs
+command-charts-type-choice-songs = Songs
+command-charts-type-choice-albums = Alben
+command-charts-type-choice-playlists = Playlists
+command-charts-type-choice-music-videos = Musikvideos
+command-charts-genre-description = Nur dieses Genre ranken
@@ -0,0 +1,212 @@
+# Conversion embeds
+listen-by = Listen to { $title } by { $artist } on Cider
+listen = Listen to { $title } on Cider
+tune-into = Tune into { $title } on Cider
+shared-by = Shared by { $name }
+play-in-cider = Play in Cider
+view-in-cider = View in Cider
+maintenance = Cidar is under maintenance, link conversion is paused for now
+lyrics-button = Lyrics
+lyrics-page = Page { $page } of { $pages } • Lyrics from Apple Music
+alternates-placeholder = Other versions…
+similar-button = Similar
+open-in-cider = Open in Cider
+similar-title = More like this
+charts-page = Page { $page } of { $pages } • Charts from Apple Music
+charts-title = Charts
+title-by = { $title } by { $artist }
+unknown-artist = Unknown artist
+nothing-shared = Nothing shared yet
+paging-previous = Previous
+paging-next = Next
+
+# Song details
+field-genre = Genre
+field-composer = Composer
+field-track = Track
+field-rating = Rating
+field-audio = Audio
+field-label = Label
+field-copyright = Copyright
+field-top-songs = Top songs
+field-latest-release = Latest release
+field-playlists = Playlists
+field-releases = Latest releases
+field-featured = Featured
+track-number = { $track } on disc { $disc }
+rating-explicit = 🅴 Explicit
+rating-clean = Clean
+
+# Media types
+media-song = Song
+media-album = Album
+media-playlist = Playlist
+media-music-video = Music video
+media-station = Station
+media-artist = Artist
+media-curator = Curator
+media-apple-curator = Apple curator
+media-radio-show = Radio show
+media-record-label = Record label
+media-editorial = Editorial
+media-social-profile = Social profile
+
+# Durations and dates
+released = Released
+duration-days = { $count ->
+    [one] { $count } day
+   *[other] { $count } days
+}
+duration-hours = { $count } hr
+duration-minutes = { $count } min
+duration-seconds = { $count } sec
+
+# Errors, every error has a title and a hint on what to do about it
+convert-invalid-input = Did not input link
+convert-invalid-input-hint = Paste the link you want to convert into the `link` option.
+convert-invalid-content = Content is not a link
+convert-invalid-content-hint = Use the full link, e.g. https://open.spotify.com/track/...
+convert-failed = Could not convert to apple music link
+convert-failed-hint = song.link couldn't find this on Apple Music, it might not be available there.
+convert-request = Request failed
+convert-request-hint = song.link didn't answer, try again in a minute.
+lyrics-invalid-input = Did not input a song
+lyrics-invalid-input-hint = Paste an Apple Music song link or type what to search for.
+lyrics-not-found = Could not find the song
+lyrics-not-found-hint = Use an Apple Music song link, or try searching with the artist's name too.
+lyrics-unavailable = No lyrics available
+lyrics-unavailable-hint = Apple Music doesn't have lyrics for this song yet.
+lyrics-request = Request failed
+lyrics-request-hint = Apple Music didn't answer, try again in a minute.
+alternate-not-found = Version not found
+alternate-not-found-hint = Apple Music doesn't have that version any more, try another one.
+similar-not-found = Nothing similar found
+similar-not-found-hint = Apple Music doesn't have recommendations for this yet.
+similar-request = Request failed
+similar-request-hint = Apple Music didn't answer, try again in a minute.
+follow-not-found = Artist not found
+follow-not-found-hint = Check the spelling or pick one of the suggestions.
+follow-request = Request failed
+follow-request-hint = Apple Music didn't answer, try again in a minute.
+follow-invalid-storefront = Unknown storefront
+follow-invalid-storefront-hint = Use a two letter country code like us, gb or de.
+history-invalid-date = Not a date
+history-invalid-date-hint = Dates look like 2023-07-14, year first.
+charts-invalid-storefront = Unknown storefront
+charts-invalid-storefront-hint = Use a two letter country code like us, gb or de.
+charts-genre-not-found = Genre not found
+charts-genre-not-found-hint = Pick one of the suggested genres for that storefront.
+charts-empty = No chart available
+charts-empty-hint = Apple Music doesn't have this chart for that storefront, try another type or genre.
+charts-request = Request failed
+charts-request-hint = Apple Music didn't answer, try again in a minute.
+storage-database = Database request failed
+storage-database-hint = The database is not reachable right now, try again in a bit.
+storage-internal = Could not access stored data
+storage-internal-hint = Something is wrong on our end, let the Cider team know if this keeps happening.
+
+# /about
+about-version = Version
+about-authors = Author(s)
+about-build-time = Build time
+about-commit = Commit hash
+about-rust = Rust version
+
+# /stats
+stats-title = Conversions
+stats-total = Total: { $total }
+stats-total-guild = Total: { $total } (this server: { $guild })
+stats-recent = Last 24 hours: { $day } • 7 days: { $week } • 30 days: { $month }
+stats-by-type = By media type (30 days)
+stats-by-platform = By platform (30 days)
+stats-top-artists = Most shared artists (30 days)
+stats-top-albums = Most shared albums (30 days)
+stats-uptime = Uptime: { $uptime }
+
+# /leaderboard
+leaderboard-guild-only = Leaderboards are only available in servers
+leaderboard-week = Leaderboard this week
+leaderboard-month = Leaderboard this month
+leaderboard-all = Leaderboard of all time
+leaderboard-sharers = Top sharers
+leaderboard-tracks = Top tracks
+leaderboard-albums = Top albums
+leaderboard-artists = Top artists
+
+# /history
+history-guild-only = History is only available in servers
+history-none = No shares match that filter
+history-title = Share history
+history-page = Page { $page } of { $pages }
+history-entry = { $title } ({ $kind }) shared by { $name }
+history-jump = jump
+
+# /config
+config-guild-only = Settings are only available in servers
+config-pick = Pick a setting to change
+config-digest-enabled = The weekly digest will be posted in { $channel }
+config-digest-disabled = The weekly digest is now disabled
+config-language-set = Cidar will now answer in { $language } in this server
+config-language-reset = Cidar will now follow the server's language
+config-field-shown = Embeds will show { $field }
+config-field-hidden = Embeds will no longer show { $field }
+config-links-buttons = Embeds will show { $buttons }
+config-links-none = Embeds will no longer show Cider buttons
+config-links-scheme-web = Links open through { $base }
+config-links-scheme-cider = Embeds will also have cider:// links that open the app directly
+
+# /follow
+follow-guild-only = Following artists is only available in servers
+follow-added = New releases from { $artist } will be posted in { $channel }
+follow-removed = No longer following { $artist }
+follow-not-following = This server doesn't follow { $artist }
+follow-none = This server doesn't follow any artists yet
+follow-list = Followed artists
+follow-limit = This server already follows { $count } artists, remove one first
+follow-list-entry = - { $artist } in { $channel }
+follow-pick = Pick what to do
+
+# /admin
+admin-owner-only = Only the bot owner can do that
+admin-pick = Pick an admin command
+admin-token-refreshed = Developer token refreshed
+admin-token-failed = Unable to refresh the developer token, keeping the previous one
+admin-cache-cleared = Cleared { $count } cached catalog responses, other shards follow within { $seconds } seconds
+admin-cache-cleared-here = Cleared { $count } cached catalog responses on this shard only, storage is not reachable
+admin-cache-catalog = Catalog cache
+admin-cache-entries = Entries: { $entries } ({ $expired } expired)
+admin-cache-hits = Hits: { $hits } • Misses: { $misses }
+admin-cache-discord = Discord cache
+admin-cache-discord-counts = Guilds: { $guilds } • Channels: { $channels } • Users: { $users }
+admin-reloaded = Configuration reloaded, { $templates } presence templates and { $admins } admins
+admin-health-ready = Ready
+admin-health-shards = Shards
+admin-health-token = Token age
+admin-health-upstreams = Last upstream success
+admin-yes = yes
+admin-no = no
+admin-shard-up = { $shard } up
+admin-shard-down = { $shard } down
+admin-token-age = { $minutes } minutes
+admin-token-never = never refreshed
+admin-none-yet = None yet
+admin-maintenance-on = Maintenance mode on, auto-conversion pauses on every shard within { $seconds } seconds
+admin-maintenance-off = Maintenance mode off, auto-conversion is back on every shard within { $seconds } seconds
+admin-maintenance-here = Maintenance mode only changed on this shard, storage is not reachable
+admin-commands-registered = Commands registered
+admin-commands-failed = Unable to register commands: { $error }
+
+# Release announcements
+new-release = New music from { $artist }
+new-release-footer = Posted because this server follows the artist
+
+# Weekly digest
+digest-title = Your weekly recap
+digest-top-sharers = Top sharers
+digest-footer = { $count ->
+    [one] { $count } link shared this week
+   *[other] { $count } links shared this week
+}
+
+# Slash command names and descriptions are written in English in their `register` functions,
+# other catalogs translate them as command-<name>[-<option>...]-name and -description.
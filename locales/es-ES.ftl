@@ -0,0 +1,314 @@
+# Conversion embeds
+listen-by = Escucha { $title } de { $artist } en Cider
+listen = Escucha { $title } en Cider
+tune-into = Sintoniza { $title } en Cider
+shared-by = Compartido por { $name }
+play-in-cider = Reproducir en Cider
+view-in-cider = Ver en Cider
+maintenance = Cidar está en mantenimiento, la conversión de enlaces está en pausa por ahora
+lyrics-button = Letra
+lyrics-page = Página { $page } de { $pages } • Letra de Apple Music
+alternates-placeholder = Otras versiones…
+similar-button = Similares
+open-in-cider = Abrir en Cider
+similar-title = Más como esto
+charts-page = Página { $page } de { $pages } • Listas de Apple Music
+charts-title = Listas
+title-by = { $title } de { $artist }
+unknown-artist = Artista desconocido
+nothing-shared = Nada compartido todavía
+paging-previous = Anterior
+paging-next = Siguiente
+
+# Song details
+field-genre = Género
+field-composer = Compositor
+field-track = Pista
+field-rating = Clasificación
+field-audio = Audio
+field-label = Sello
+field-copyright = Copyright
+field-top-songs = Canciones populares
+field-latest-release = Último lanzamiento
+field-playlists = Playlists
+field-releases = Últimos lanzamientos
+field-featured = Destacado
+track-number = { $track } del disco { $disc }
+rating-explicit = 🅴 Explícito
+rating-clean = Sin contenido explícito
+
+# Tipos de contenido
+media-song = Canción
+media-album = Álbum
+media-playlist = Playlist
+media-music-video = Videoclip
+media-station = Emisora
+media-artist = Artista
+media-curator = Curador
+media-apple-curator = Curador de Apple
+media-radio-show = Programa de radio
+media-record-label = Sello discográfico
+media-editorial = Editorial
+media-social-profile = Perfil
+
+# Durations and dates
+released = Lanzamiento
+duration-days = { $count ->
+    [one] { $count } día
+   *[other] { $count } días
+}
+duration-hours = { $count } h
+duration-minutes = { $count } min
+duration-seconds = { $count } s
+
+# Errors
+convert-invalid-input = No se indicó ningún enlace
+convert-invalid-input-hint = Pega el enlace que quieres convertir en la opción `link`.
+convert-invalid-content = Eso no es un enlace
+convert-invalid-content-hint = Usa el enlace completo, p. ej. https://open.spotify.com/track/...
+convert-failed = No se pudo convertir a un enlace de Apple Music
+convert-failed-hint = song.link no lo encontró en Apple Music, puede que no esté disponible allí.
+convert-request = La solicitud falló
+convert-request-hint = song.link no respondió, inténtalo de nuevo en un minuto.
+lyrics-invalid-input = No se indicó ninguna canción
+lyrics-invalid-input-hint = Pega un enlace de Apple Music o escribe lo que quieres buscar.
+lyrics-not-found = No se encontró la canción
+lyrics-not-found-hint = Usa un enlace de Apple Music o busca también con el nombre del artista.
+lyrics-unavailable = No hay letra disponible
+lyrics-unavailable-hint = Apple Music todavía no tiene la letra de esta canción.
+lyrics-request = La solicitud falló
+lyrics-request-hint = Apple Music no respondió, inténtalo de nuevo en un minuto.
+alternate-not-found = Versión no encontrada
+alternate-not-found-hint = Apple Music ya no tiene esa versión, prueba con otra.
+similar-not-found = No se encontró nada similar
+similar-not-found-hint = Apple Music aún no tiene recomendaciones para esto.
+similar-request = La solicitud falló
+similar-request-hint = Apple Music no respondió, inténtalo de nuevo en un minuto.
+follow-not-found = Artista no encontrado
+follow-not-found-hint = Revisa cómo está escrito o elige una de las sugerencias.
+follow-request = La solicitud falló
+follow-request-hint = Apple Music no respondió, inténtalo de nuevo en un minuto.
+follow-invalid-storefront = Tienda desconocida
+follow-invalid-storefront-hint = Usa un código de país de dos letras como us, gb o es.
+history-invalid-date = No es una fecha
+history-invalid-date-hint = Las fechas se escriben como 2023-07-14, con el año primero.
+charts-invalid-storefront = Tienda desconocida
+charts-invalid-storefront-hint = Usa un código de país de dos letras como us, gb o es.
+charts-genre-not-found = Género no encontrado
+charts-genre-not-found-hint = Elige uno de los géneros sugeridos para esa tienda.
+charts-empty = No hay lista disponible
+charts-empty-hint = Apple Music no tiene esta lista para esa tienda, prueba con otro tipo o género.
+charts-request = La solicitud falló
+charts-request-hint = Apple Music no respondió, inténtalo de nuevo en un minuto.
+storage-database = La consulta a la base de datos falló
+storage-database-hint = La base de datos no está disponible ahora mismo, inténtalo de nuevo en un rato.
+storage-internal = No se pudieron leer los datos guardados
+storage-internal-hint = Algo falló por nuestra parte, avisa al equipo de Cider si sigue pasando.
+
+# /about
+about-version = Versión
+about-authors = Autor(es)
+about-build-time = Fecha de compilación
+about-commit = Hash del commit
+about-rust = Versión de Rust
+
+# /stats
+stats-title = Conversiones
+stats-total = Total: { $total }
+stats-total-guild = Total: { $total } (este servidor: { $guild })
+stats-recent = Últimas 24 horas: { $day } • 7 días: { $week } • 30 días: { $month }
+stats-by-type = Por tipo de contenido (30 días)
+stats-by-platform = Por plataforma (30 días)
+stats-top-artists = Artistas más compartidos (30 días)
+stats-top-albums = Álbumes más compartidos (30 días)
+stats-uptime = Tiempo activo: { $uptime }
+
+# /leaderboard
+leaderboard-guild-only = Las clasificaciones solo están disponibles en servidores
+leaderboard-week = Clasificación de esta semana
+leaderboard-month = Clasificación de este mes
+leaderboard-all = Clasificación de siempre
+leaderboard-sharers = Quienes más comparten
+leaderboard-tracks = Canciones más compartidas
+leaderboard-albums = Álbumes más compartidos
+leaderboard-artists = Artistas más compartidos
+
+# /history
+history-guild-only = El historial solo está disponible en servidores
+history-none = Nada compartido coincide con ese filtro
+history-title = Historial
+history-page = Página { $page } de { $pages }
+history-entry = { $title } ({ $kind }) compartido por { $name }
+history-jump = ver
+
+# /config
+config-guild-only = Los ajustes solo están disponibles en servidores
+config-pick = Elige un ajuste para cambiar
+config-digest-enabled = El resumen semanal se publicará en { $channel }
+config-digest-disabled = El resumen semanal está desactivado
+config-language-set = Cidar ahora responderá en { $language } en este servidor
+config-language-reset = Cidar ahora seguirá el idioma del servidor
+config-field-shown = Los embeds mostrarán { $field }
+config-field-hidden = Los embeds ya no mostrarán { $field }
+config-links-buttons = Los embeds mostrarán { $buttons }
+config-links-none = Los embeds ya no mostrarán botones de Cider
+config-links-scheme-web = Los enlaces se abren a través de { $base }
+config-links-scheme-cider = Los embeds también tendrán enlaces cider:// que abren la app directamente
+
+# /follow
+follow-guild-only = Seguir artistas solo está disponible en servidores
+follow-added = Los nuevos lanzamientos de { $artist } se publicarán en { $channel }
+follow-removed = Ya no se sigue a { $artist }
+follow-not-following = Este servidor no sigue a { $artist }
+follow-none = Este servidor todavía no sigue a ningún artista
+follow-list = Artistas seguidos
+follow-limit = Este servidor ya sigue a { $count } artistas, elimina uno primero
+follow-list-entry = - { $artist } en { $channel }
+follow-pick = Elige qué hacer
+
+# /admin
+admin-owner-only = Solo el dueño del bot puede hacer eso
+admin-pick = Elige un comando de administración
+admin-token-refreshed = Token de desarrollador renovado
+admin-token-failed = No se pudo renovar el token de desarrollador, se mantiene el anterior
+admin-cache-cleared = Se borraron { $count } respuestas del catálogo en caché, los demás shards lo harán en { $seconds } segundos
+admin-cache-cleared-here = Se borraron { $count } respuestas del catálogo en caché solo en este shard, el almacenamiento no está disponible
+admin-cache-catalog = Caché del catálogo
+admin-cache-entries = Entradas: { $entries } ({ $expired } caducadas)
+admin-cache-hits = Aciertos: { $hits } • Fallos: { $misses }
+admin-cache-discord = Caché de Discord
+admin-cache-discord-counts = Servidores: { $guilds } • Canales: { $channels } • Usuarios: { $users }
+admin-reloaded = Configuración recargada, { $templates } plantillas de estado y { $admins } administradores
+admin-health-ready = Listo
+admin-health-shards = Shards
+admin-health-token = Edad del token
+admin-health-upstreams = Última respuesta correcta
+admin-yes = sí
+admin-no = no
+admin-shard-up = { $shard } activo
+admin-shard-down = { $shard } caído
+admin-token-age = { $minutes } minutos
+admin-token-never = nunca renovado
+admin-none-yet = Ninguna todavía
+admin-maintenance-on = Modo mantenimiento activado, la conversión automática se pausa en todos los shards en { $seconds } segundos
+admin-maintenance-off = Modo mantenimiento desactivado, la conversión automática vuelve en todos los shards en { $seconds } segundos
+admin-maintenance-here = El modo mantenimiento solo cambió en este shard, el almacenamiento no está disponible
+admin-commands-registered = Comandos registrados
+admin-commands-failed = No se pudieron registrar los comandos: { $error }
+
+# Release announcements
+new-release = Música nueva de { $artist }
+new-release-footer = Publicado porque este servidor sigue al artista
+
+# Weekly digest
+digest-title = Vuestro resumen semanal
+digest-top-sharers = Quién más compartió
+digest-footer = { $count ->
+    [one] { $count } enlace compartido esta semana
+   *[other] { $count } enlaces compartidos esta semana
+}
+
+# Slash commands
+command-about-description = Información sobre Cidar
+command-convert-name = convertir
+command-convert-description = Convierte cualquier enlace en su equivalente de Apple Music
+command-convert-link-description = Enlace del contenido
+command-stats-name = estadisticas
+command-stats-description = Muestra estadísticas de conversiones
+command-leaderboard-name = clasificacion
+command-leaderboard-description = Quién comparte más música y qué se comparte más
+command-leaderboard-window-name = periodo
+command-leaderboard-window-description = Periodo de la clasificación
+command-leaderboard-window-choice-week = Semanal
+command-leaderboard-window-choice-month = Mensual
+command-leaderboard-window-choice-all = Desde siempre
+command-history-name = historial
+command-history-description = Busca la música compartida en este servidor
+command-history-user-name = miembro
+command-history-user-description = Solo lo compartido por este miembro
+command-history-artist-name = artista
+command-history-artist-description = Solo música de este artista
+command-history-type-name = tipo
+command-history-type-description = Solo este tipo de contenido
+command-history-type-choice-so = { media-song }
+command-history-type-choice-al = { media-album }
+command-history-type-choice-pl = { media-playlist }
+command-history-type-choice-mv = { media-music-video }
+command-history-type-choice-st = { media-station }
+command-history-type-choice-ar = { media-artist }
+command-history-type-choice-cu = { media-curator }
+command-history-type-choice-ac = { media-apple-curator }
+command-history-type-choice-rs = { media-radio-show }
+command-history-type-choice-rl = { media-record-label }
+command-history-type-choice-ed = { media-editorial }
+command-history-type-choice-sp = { media-social-profile }
+command-history-date-name = fecha
+command-history-date-description = Solo lo compartido este día, p. ej. 2023-07-14
+command-config-description = Cambia cómo se comporta Cidar en este servidor
+command-config-digest-name = resumen
+command-config-digest-description = Publica un resumen semanal de la música compartida, déjalo vacío para desactivarlo
+command-config-digest-channel-name = canal
+command-config-digest-channel-description = Canal donde publicar el resumen
+command-config-language-name = idioma
+command-config-language-description = Idioma de los mensajes de Cidar, déjalo vacío para usar el del servidor
+command-config-language-locale-name = idioma
+command-config-language-locale-description = El idioma que debe usar Cidar
+command-config-fields-name = detalles
+command-config-fields-description = Muestra u oculta detalles en los embeds
+command-config-fields-field-name = detalle
+command-config-fields-field-description = El detalle que quieres cambiar
+command-config-fields-field-choice-field-genre = { field-genre }
+command-config-fields-field-choice-field-composer = { field-composer }
+command-config-fields-field-choice-field-track = { field-track }
+command-config-fields-field-choice-field-rating = { field-rating }
+command-config-fields-field-choice-field-audio = { field-audio }
+command-config-fields-field-choice-field-label = { field-label }
+command-config-fields-field-choice-field-copyright = { field-copyright }
+command-config-fields-field-choice-field-top-songs = { field-top-songs }
+command-config-fields-field-choice-field-latest-release = { field-latest-release }
+command-config-fields-field-choice-field-playlists = { field-playlists }
+command-config-fields-field-choice-field-releases = { field-releases }
+command-config-fields-field-choice-field-featured = { field-featured }
+command-config-fields-shown-name = mostrar
+command-config-fields-shown-description = Si se debe mostrar
+command-config-links-name = enlaces
+command-config-links-description = Elige los botones de Cider en los embeds y cómo se abren sus enlaces
+command-config-links-play-name = reproducir
+command-config-links-play-description = Mostrar el botón Reproducir en Cider
+command-config-links-view-name = ver
+command-config-links-view-description = Mostrar el botón Ver en Cider
+command-config-links-scheme-name = esquema
+command-config-links-scheme-description = También añadir enlaces cider:// que abren la app directamente
+command-config-links-scheme-choice-web = Redirección web
+command-config-links-scheme-choice-cider = App de Cider
+command-lyrics-name = letra
+command-lyrics-description = Busca la letra de una canción
+command-lyrics-song-description = Un enlace de Apple Music o lo que quieres buscar
+command-follow-name = seguir
+command-follow-description = Publica los nuevos lanzamientos de artistas en este servidor
+command-follow-artist-name = artista
+command-follow-artist-description = Seguir a un artista
+command-follow-artist-name-name = nombre
+command-follow-artist-name-description = El artista a seguir
+command-follow-artist-channel-name = canal
+command-follow-artist-channel-description = Canal para los nuevos lanzamientos, este si se deja vacío
+command-follow-artist-storefront-name = tienda
+command-follow-artist-storefront-description = Código de país de la tienda en la que seguirlo, us si se deja vacío
+command-follow-remove-name = quitar
+command-follow-remove-description = Dejar de seguir a un artista
+command-follow-remove-name-name = nombre
+command-follow-remove-name-description = El artista que se dejará de seguir
+command-follow-list-name = lista
+command-follow-list-description = Muestra los artistas que sigue este servidor
+command-charts-name = listas
+command-charts-description = Muestra las listas de Apple Music
+command-charts-storefront-name = tienda
+command-charts-storefront-description = Código de país de las listas, p. ej. us o es
+command-charts-type-name = tipo
+command-charts-type-description = Qué clasificar, canciones por defecto
+command-charts-type-choice-songs = Canciones
+command-charts-type-choice-albums = Álbumes
+command-charts-type-choice-playlists = Playlists
+command-charts-type-choice-music-videos = Videoclips
+command-charts-genre-name = genero
+command-charts-genre-description = Clasificar solo este género
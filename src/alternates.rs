@@ -0,0 +1,316 @@
+use std::collections::HashSet;
+
+use log::*;
+use reqwest::Method;
+use serde_json::Value;
+use serenity::builder::CreateComponents;
+use serenity::model::id::UserId;
+use serenity::prelude::Context;
+use thiserror::Error;
+
+use crate::{
+    api::AppleMusicApi,
+    cider_links::LinkButtons,
+    commands::Hint,
+    conversion::{self, EmbedInformation},
+    tr, ValuePath,
+};
+
+// Discord allows 25 options, more than a handful of versions is just noise though.
+const MAX_ALTERNATES: usize = 10;
+const MAX_ALBUMS: usize = 10;
+
+#[derive(Error, Debug)]
+pub enum AlternateError {
+    #[error("could not load that version")]
+    NotFound,
+}
+
+impl Hint for AlternateError {
+    fn id(&self) -> &'static str {
+        match self {
+            AlternateError::NotFound => "alternate-not-found",
+        }
+    }
+}
+
+/// Another version of a song, the clean or explicit one, a remaster, a live recording...
+#[derive(Debug)]
+pub struct Alternate {
+    pub id: String,
+    pub name: String,
+    pub album: Option<String>,
+    pub explicit: Option<bool>,
+}
+
+impl Alternate {
+    fn from_song(song: &Value) -> Option<Alternate> {
+        let attribute = |name: &str| {
+            song.get_value_by_path(&format!("attributes.{name}"))
+                .and_then(|value| value.as_str().map(str::to_string))
+        };
+
+        Some(Alternate {
+            id: song.get_value_by_path("id")?.as_str()?.to_string(),
+            name: attribute("name")?,
+            album: attribute("albumName"),
+            explicit: attribute("contentRating").map(|rating| rating == "explicit"),
+        })
+    }
+
+    fn description(&self, locale: &str) -> String {
+        let rating = self.explicit.map(|explicit| match explicit {
+            true => tr!(locale, "rating-explicit"),
+            false => tr!(locale, "rating-clean"),
+        });
+
+        [self.album.clone(), rating]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+/// "Song (Remastered 2011) - Live" and "Song" are the same song for our purposes.
+fn base_name(name: &str) -> String {
+    let end = [" (", " [", " - "]
+        .iter()
+        .filter_map(|separator| name.find(separator))
+        .min()
+        .unwrap_or(name.len());
+
+    name[..end].trim().to_lowercase()
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some(_) => format!("{}…", text.chars().take(max - 1).collect::<String>()),
+        None => text.to_string(),
+    }
+}
+
+/// Looks for other versions of a song: its catalog equivalents (usually the clean or explicit
+/// counterpart) and the same track on other versions of its album.
+pub async fn find(api: &AppleMusicApi, storefront: &str, id: &str) -> Vec<Alternate> {
+    // Same request as the conversion, so this comes out of the cache.
+    let Ok(song) = api
+        .request_endpoint(Method::GET, &conversion::song_endpoint(storefront, id))
+        .await
+    else {
+        warn!("Unable to request song {id} for its alternate versions");
+        return Vec::new();
+    };
+
+    let Some(name) = song
+        .get_value_by_path("data.0.attributes.name")
+        .and_then(|name| name.as_str().map(base_name))
+    else {
+        return Vec::new();
+    };
+
+    let mut seen = HashSet::from([id.to_string()]);
+    let mut alternates = Vec::new();
+    let mut add = |song: &Value| {
+        let Some(alternate) = Alternate::from_song(song) else {
+            return;
+        };
+
+        if alternates.len() < MAX_ALTERNATES && seen.insert(alternate.id.clone()) {
+            alternates.push(alternate);
+        }
+    };
+
+    match api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/songs?filter[equivalents]={id}"),
+        )
+        .await
+    {
+        Ok(equivalents) => {
+            if let Some(Value::Array(songs)) = equivalents.get_value_by_path("data") {
+                songs.iter().for_each(&mut add);
+            }
+        }
+        Err(e) => warn!("Unable to request the equivalents of {id}: {e}"),
+    }
+
+    let album_id = song
+        .get_value_by_path("data.0.relationships.albums.data.0.id")
+        .and_then(|id| id.as_str().map(str::to_string));
+
+    let Some(album_id) = album_id else {
+        return alternates;
+    };
+
+    let album_ids = match api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/albums/{album_id}/view/other-versions"),
+        )
+        .await
+    {
+        Ok(versions) => match versions.get_value_by_path("data") {
+            Some(Value::Array(albums)) => albums
+                .iter()
+                .filter_map(|album| album.get_value_by_path("id")?.as_str().map(str::to_string))
+                .take(MAX_ALBUMS)
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        },
+        Err(e) => {
+            warn!("Unable to request the other versions of album {album_id}: {e}");
+            Vec::new()
+        }
+    };
+
+    if album_ids.is_empty() {
+        return alternates;
+    }
+
+    match api
+        .request_endpoint(
+            Method::GET,
+            &format!(
+                "v1/catalog/{storefront}/albums?ids={}&include=tracks",
+                album_ids.join(",")
+            ),
+        )
+        .await
+    {
+        Ok(albums) => {
+            let Some(Value::Array(albums)) = albums.get_value_by_path("data") else {
+                return alternates;
+            };
+
+            for album in &albums {
+                let Some(Value::Array(tracks)) =
+                    album.get_value_by_path("relationships.tracks.data")
+                else {
+                    continue;
+                };
+
+                tracks
+                    .iter()
+                    .filter(|track| {
+                        track
+                            .get_value_by_path("attributes.name")
+                            .and_then(|track| track.as_str().map(base_name))
+                            .is_some_and(|track| track == name)
+                    })
+                    .for_each(&mut add);
+            }
+        }
+        Err(e) => warn!("Unable to request the tracks of the other versions of {album_id}: {e}"),
+    }
+
+    alternates
+}
+
+/// Adds the select menu for picking another version, nothing if there are none.
+pub fn menu<'a>(
+    c: &'a mut CreateComponents,
+    current: &EmbedInformation,
+    alternates: &[Alternate],
+    author_id: u64,
+    locale: &str,
+) -> &'a mut CreateComponents {
+    if alternates.is_empty() {
+        return c;
+    }
+
+    c.create_action_row(|r| {
+        r.create_select_menu(|m| {
+            m.custom_id(format!("alt:{}:{author_id}", current.storefront))
+                .placeholder(tr!(locale, "alternates-placeholder"))
+                .options(|o| {
+                    // Keep the song on the embed in the list, selected, so it can be swapped back.
+                    o.create_option(|o| {
+                        o.label(truncate(&current.title, 100))
+                            .value(&current.catalog_id)
+                            .default_selection(true);
+                        if let Some(album) = &current.album {
+                            o.description(truncate(album, 100));
+                        }
+                        o
+                    });
+
+                    for alternate in alternates {
+                        o.create_option(|o| {
+                            o.label(truncate(&alternate.name, 100)).value(&alternate.id);
+
+                            let description = alternate.description(locale);
+                            if !description.is_empty() {
+                                o.description(truncate(&description, 100));
+                            }
+                            o
+                        });
+                    }
+                    o
+                })
+        })
+    })
+}
+
+/// The version someone picked from the menu, rebuilt like it was shared in the first place.
+pub struct Version {
+    pub information: EmbedInformation,
+    pub alternates: Vec<Alternate>,
+    pub author_id: u64,
+}
+
+impl Version {
+    pub fn components<'a>(
+        &self,
+        c: &'a mut CreateComponents,
+        locale: &str,
+        links: &LinkButtons,
+    ) -> &'a mut CreateComponents {
+        self.information.buttons(c, locale, links);
+        menu(
+            c,
+            &self.information,
+            &self.alternates,
+            self.author_id,
+            locale,
+        )
+    }
+}
+
+/// Handles a pick from the menu, returns `None` if the custom id isn't ours.
+pub async fn swap(
+    custom_id: &str,
+    song_id: &str,
+    api: &AppleMusicApi,
+    ctx: &Context,
+    locale: &str,
+) -> Option<Result<Version, AlternateError>> {
+    let mut parts = custom_id.strip_prefix("alt:")?.split(':');
+
+    let storefront = parts.next()?.to_string();
+    let author_id = parts.next()?.parse::<u64>().ok()?;
+
+    // The footer still credits whoever shared the song, not whoever picked another version.
+    let shared_by = match UserId(author_id).to_user(ctx).await {
+        Ok(user) => user.name,
+        Err(e) => {
+            warn!("Unable to look up user {author_id}: {e}");
+            String::new()
+        }
+    };
+
+    let Some(information) =
+        conversion::song_information(api, &storefront, song_id, &shared_by, locale).await
+    else {
+        return Some(Err(AlternateError::NotFound));
+    };
+
+    let alternates = find(api, &storefront, &information.catalog_id).await;
+
+    Some(Ok(Version {
+        information,
+        alternates,
+        author_id,
+    }))
+}
@@ -7,19 +7,34 @@ use reqwest::{
 use serde_json::Value;
 use tokio::sync::RwLock;
 
-use crate::TokenLock;
+use crate::{
+    cache::TtlCache,
+    health::{Health, Upstream},
+    TokenLock,
+};
 
 pub struct AppleMusicApi {
     pub client: Arc<RwLock<reqwest::Client>>,
     pub developer_token: TokenLock,
+    pub health: Arc<Health>,
+    // Catalog data barely changes, so popular links don't have to hit apple every time.
+    pub cache: TtlCache<Value>,
 }
 
 impl AppleMusicApi {
+    #[tracing::instrument(name = "apple_music", skip(self))]
     pub async fn request_endpoint(
         &self,
         method: Method,
         endpoint: &str,
     ) -> Result<Value, reqwest::Error> {
+        let cacheable = method == Method::GET;
+        if cacheable {
+            if let Some(cached) = self.cache.get(endpoint).await {
+                return Ok(cached);
+            }
+        }
+
         let req = self
             .client
             .read()
@@ -30,7 +45,16 @@ impl AppleMusicApi {
             ))
             .send()
             .await?;
-        req.json().await
+        let json: Value = req.json().await?;
+
+        self.health.upstream_ok(Upstream::AppleMusic).await;
+
+        // Errors come back as json too, only keep the real answers around.
+        if cacheable && json.get("errors").is_none() {
+            self.cache.insert(endpoint.to_string(), json.clone()).await;
+        }
+
+        Ok(json)
     }
 
     fn build_headers(token: &String) -> HeaderMap {
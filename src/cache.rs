@@ -0,0 +1,81 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use tokio::sync::RwLock;
+
+/// A small in-memory cache whose entries expire after a fixed time to live.
+#[derive(Debug)]
+pub struct TtlCache<V> {
+    ttl: Duration,
+    entries: RwLock<HashMap<String, (Instant, V)>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CacheStats {
+    pub entries: usize,
+    pub expired: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub fn new(ttl: Duration) -> TtlCache<V> {
+        TtlCache {
+            ttl,
+            entries: Default::default(),
+            hits: Default::default(),
+            misses: Default::default(),
+        }
+    }
+
+    pub async fn get(&self, key: &str) -> Option<V> {
+        let value = self
+            .entries
+            .read()
+            .await
+            .get(key)
+            .filter(|(at, _)| at.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone());
+
+        match value {
+            Some(_) => self.hits.fetch_add(1, Ordering::Relaxed),
+            None => self.misses.fetch_add(1, Ordering::Relaxed),
+        };
+
+        value
+    }
+
+    pub async fn insert(&self, key: String, value: V) {
+        let mut entries = self.entries.write().await;
+
+        // Drop anything stale while we hold the lock anyway, so the map doesn't grow forever.
+        entries.retain(|_, (at, _)| at.elapsed() < self.ttl);
+        entries.insert(key, (Instant::now(), value));
+    }
+
+    pub async fn clear(&self) -> usize {
+        let mut entries = self.entries.write().await;
+        let cleared = entries.len();
+        entries.clear();
+        cleared
+    }
+
+    pub async fn stats(&self) -> CacheStats {
+        let entries = self.entries.read().await;
+
+        CacheStats {
+            entries: entries.len(),
+            expired: entries
+                .values()
+                .filter(|(at, _)| at.elapsed() >= self.ttl)
+                .count(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
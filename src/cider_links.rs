@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+
+use log::*;
+use once_cell::sync::Lazy;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::conversion::MediaType;
+
+const DEFAULT_BASE: &str = "https://cider.sh";
+
+/// Where the web redirect lives, `CIDER_LINK_BASE` points it somewhere else for testing or
+/// self-hosted forks. A path is kept, so `https://example.com/cider/` redirects through
+/// `https://example.com/cider/p`. Read once on startup, `/admin reload` doesn't change it.
+pub static BASE: Lazy<Url> = Lazy::new(|| {
+    let base = std::env::var("CIDER_LINK_BASE").unwrap_or(DEFAULT_BASE.to_string());
+    Url::parse(&base).unwrap_or_else(|e| {
+        warn!("CIDER_LINK_BASE {base} is not a valid url ({e}), using {DEFAULT_BASE}");
+        Url::parse(DEFAULT_BASE).unwrap()
+    })
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Play,
+    View,
+}
+
+impl Action {
+    /// The path of the action on the web redirect.
+    fn redirect(&self) -> &'static str {
+        match self {
+            Action::Play => "p",
+            Action::View => "o",
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Play => "play",
+            Action::View => "view",
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkScheme {
+    /// Through the web redirect, which works everywhere.
+    #[default]
+    Web,
+    /// Straight into the app with `cider://`.
+    Cider,
+}
+
+/// Which Cider buttons a guild wants on its embeds, changed with `/config links`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LinkButtons {
+    pub play: bool,
+    pub view: bool,
+    pub scheme: LinkScheme,
+}
+
+impl LinkButtons {
+    /// What names linked in embed text open, following the buttons the guild kept. `None`
+    /// when both are off, the names are left unlinked then.
+    pub fn text_action(&self) -> Option<Action> {
+        match (self.play, self.view) {
+            (true, _) => Some(Action::Play),
+            (false, true) => Some(Action::View),
+            (false, false) => None,
+        }
+    }
+
+    /// Where a name linked in embed text goes. Straight to apple music when the guild turned
+    /// both buttons off, for lists that are no use without links.
+    pub fn text_url(&self, link: &str) -> String {
+        match self.text_action() {
+            Some(action) => web(link, action),
+            None => link.to_string(),
+        }
+    }
+}
+
+impl Default for LinkButtons {
+    fn default() -> Self {
+        LinkButtons {
+            play: true,
+            view: true,
+            scheme: LinkScheme::Web,
+        }
+    }
+}
+
+/// Something in the catalog, as far as Cider cares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CatalogRef {
+    pub storefront: String,
+    pub kind: MediaType,
+    pub id: String,
+}
+
+impl CatalogRef {
+    pub fn new(storefront: &str, kind: MediaType, id: &str) -> CatalogRef {
+        CatalogRef {
+            storefront: storefront.to_string(),
+            kind,
+            id: id.to_string(),
+        }
+    }
+
+    /// Reads an apple music link. Songs linked on their album (`?i=`) point at the song itself.
+    pub fn parse(link: &str) -> Option<CatalogRef> {
+        let url = Url::parse(link)
+            .or_else(|_| Url::parse(&format!("https://{link}")))
+            .ok()?;
+
+        if url.host_str()? != "music.apple.com" {
+            return None;
+        }
+
+        let query = url
+            .query_pairs()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect::<HashMap<_, _>>();
+
+        let kind = MediaType::determine(&url, &query)?;
+        let mut segments = url.path_segments()?;
+        let storefront = segments.next()?;
+
+        let id = match query.get("i") {
+            Some(song) => song.as_str(),
+            None => segments.next_back()?,
+        };
+
+        Some(CatalogRef::new(storefront, kind, id))
+    }
+
+    /// The apple music link without the name slug, which apple doesn't need.
+    fn apple_music(&self) -> String {
+        format!(
+            "music.apple.com/{}/{}/{}",
+            self.storefront,
+            self.kind.segment(),
+            self.id
+        )
+    }
+
+    fn web_on(&self, base: &Url, action: Action) -> String {
+        redirect(base, action, &self.apple_music())
+    }
+
+    /// Through the web redirect, e.g. `https://cider.sh/p?music.apple.com%2Fus%2Falbum%2F123`.
+    pub fn web(&self, action: Action) -> String {
+        self.web_on(&BASE, action)
+    }
+
+    /// Straight into the app, e.g. `cider://play/us/album/123`.
+    pub fn deep(&self, action: Action) -> String {
+        format!(
+            "cider://{}/{}/{}/{}",
+            action.name(),
+            self.storefront,
+            self.kind.segment(),
+            self.id
+        )
+    }
+}
+
+fn redirect(base: &Url, action: Action, target: &str) -> String {
+    let mut link = base.clone();
+    let path = format!(
+        "{}/{}",
+        base.path().trim_end_matches('/'),
+        action.redirect()
+    );
+    link.set_path(&path);
+    link.query_pairs_mut().append_key_only(target);
+    link.to_string()
+}
+
+/// A web redirect for any link, going through [`CatalogRef`] when it's an apple music one.
+pub fn web(link: &str, action: Action) -> String {
+    match CatalogRef::parse(link) {
+        Some(catalog) => catalog.web(action),
+        None => redirect(&BASE, action, link.trim_start_matches("https://")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_album() {
+        assert_eq!(
+            CatalogRef::parse("https://music.apple.com/us/album/folklore/1528112358"),
+            Some(CatalogRef::new("us", MediaType::Album, "1528112358"))
+        );
+    }
+
+    #[test]
+    fn parse_song_on_album() {
+        let catalog =
+            CatalogRef::parse("https://music.apple.com/gb/album/folklore/1528112358?i=1528112362")
+                .unwrap();
+
+        assert_eq!(catalog.kind, MediaType::Song);
+        assert_eq!(catalog.id, "1528112362");
+        assert_eq!(catalog.storefront, "gb");
+    }
+
+    #[test]
+    fn parse_rejects_other_sites() {
+        assert_eq!(
+            CatalogRef::parse("https://open.spotify.com/track/4iV5W9uYEdYUVa79Axb7Rh"),
+            None
+        );
+    }
+
+    #[test]
+    fn web_link_is_encoded() {
+        let catalog =
+            CatalogRef::parse("https://music.apple.com/us/album/folklore/1528112358?i=1528112362")
+                .unwrap();
+
+        assert_eq!(
+            catalog.web_on(&Url::parse(DEFAULT_BASE).unwrap(), Action::Play),
+            "https://cider.sh/p?music.apple.com%2Fus%2Fsong%2F1528112362"
+        );
+    }
+
+    #[test]
+    fn web_link_on_other_base() {
+        let catalog = CatalogRef::parse("https://music.apple.com/us/playlist/pl.123").unwrap();
+
+        assert_eq!(
+            catalog.web_on(&Url::parse("http://localhost:8080").unwrap(), Action::View),
+            "http://localhost:8080/o?music.apple.com%2Fus%2Fplaylist%2Fpl.123"
+        );
+    }
+
+    #[test]
+    fn web_link_keeps_the_base_path() {
+        let catalog =
+            CatalogRef::parse("https://music.apple.com/us/album/folklore/1528112358").unwrap();
+
+        for base in ["https://example.com/cider", "https://example.com/cider/"] {
+            assert_eq!(
+                catalog.web_on(&Url::parse(base).unwrap(), Action::Play),
+                "https://example.com/cider/p?music.apple.com%2Fus%2Falbum%2F1528112358"
+            );
+        }
+    }
+
+    #[test]
+    fn other_links_fall_back_to_the_redirect() {
+        let base = Url::parse(DEFAULT_BASE).unwrap();
+
+        assert_eq!(
+            redirect(&base, Action::View, "example.com/not/apple?x=1"),
+            "https://cider.sh/o?example.com%2Fnot%2Fapple%3Fx%3D1"
+        );
+        assert_eq!(
+            web("https://example.com/not/apple?x=1", Action::View),
+            redirect(&BASE, Action::View, "example.com/not/apple?x=1")
+        );
+    }
+
+    #[test]
+    fn text_links_follow_the_buttons() {
+        let link = "https://music.apple.com/us/artist/taylor-swift/159260351";
+        let view_only = LinkButtons {
+            play: false,
+            ..Default::default()
+        };
+        let none = LinkButtons {
+            play: false,
+            view: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            LinkButtons::default().text_url(link),
+            web(link, Action::Play)
+        );
+        assert_eq!(view_only.text_url(link), web(link, Action::View));
+        assert_eq!(none.text_url(link), link);
+    }
+
+    #[test]
+    fn deep_link() {
+        let catalog =
+            CatalogRef::parse("https://music.apple.com/us/artist/taylor-swift/159260351").unwrap();
+
+        assert_eq!(
+            catalog.deep(Action::View),
+            "cider://view/us/artist/159260351"
+        );
+    }
+}
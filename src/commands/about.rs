@@ -1,4 +1,4 @@
-use crate::util::split_authors;
+use crate::{tr, util::split_authors};
 use serenity::builder::CreateApplicationCommand;
 use serenity::model::prelude::interaction::application_command::CommandDataOption;
 
@@ -12,19 +12,29 @@ impl NaIfNone for Option<&str> {
     }
 }
 
-pub fn run(_options: &[CommandDataOption]) -> String {
-    format!(
-        "Version: {}
-Author(s): {}
-Build time: {}
-Commit hash: [{hash}](https://github.com/ciderapp/Cidar/commit/{hash})
-Rust version: {}",
-        option_env!("CARGO_PKG_VERSION").na(),
-        split_authors(&option_env!("CARGO_PKG_AUTHORS").na()),
-        option_env!("VERGEN_BUILD_TIMESTAMP").na(),
-        option_env!("VERGEN_RUSTC_SEMVER").na(),
-        hash = option_env!("VERGEN_GIT_SHA").na()
-    )
+pub fn run(_options: &[CommandDataOption], locale: &str) -> String {
+    let hash = option_env!("VERGEN_GIT_SHA").na();
+
+    [
+        ("about-version", option_env!("CARGO_PKG_VERSION").na()),
+        (
+            "about-authors",
+            split_authors(&option_env!("CARGO_PKG_AUTHORS").na()),
+        ),
+        (
+            "about-build-time",
+            option_env!("VERGEN_BUILD_TIMESTAMP").na(),
+        ),
+        (
+            "about-commit",
+            format!("[{hash}](https://github.com/ciderapp/Cidar/commit/{hash})"),
+        ),
+        ("about-rust", option_env!("VERGEN_RUSTC_SEMVER").na()),
+    ]
+    .into_iter()
+    .map(|(id, value)| format!("{}: {value}", tr!(locale, id)))
+    .collect::<Vec<_>>()
+    .join("\n")
 }
 
 pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
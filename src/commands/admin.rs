@@ -0,0 +1,297 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use log::*;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::{Permissions, Timestamp};
+use serenity::prelude::Context;
+use tokio::sync::RwLock;
+
+use crate::{
+    api::AppleMusicApi,
+    config::Config,
+    storage::Storage,
+    tr,
+    updater::{self, RUNTIME_SYNC_INTERVAL},
+};
+
+/// Everything the admin commands are allowed to poke at while the bot is running.
+pub struct Runtime<'a> {
+    pub api: &'a AppleMusicApi,
+    pub storage: &'a Storage,
+    pub config: &'a RwLock<Config>,
+    pub maintenance: &'a AtomicBool,
+}
+
+/// The application owner, their team, and anyone in `ADMIN_IDS` may run admin commands.
+async fn is_admin(ctx: &Context, user_id: u64, config: &RwLock<Config>) -> bool {
+    if config.read().await.admins.contains(&user_id) {
+        return true;
+    }
+
+    match ctx.http.get_current_application_info().await {
+        Ok(info) => {
+            info.owner.id.0 == user_id
+                || info.team.is_some_and(|team| {
+                    team.members
+                        .iter()
+                        .any(|member| member.user.id.0 == user_id)
+                })
+        }
+        Err(why) => {
+            warn!("Unable to look up the application owner: {why}");
+            false
+        }
+    }
+}
+
+async fn cache(
+    options: &[CommandDataOption],
+    ctx: &Context,
+    runtime: &Runtime<'_>,
+    locale: &str,
+) -> String {
+    let clear = matches!(
+        options.first().and_then(|o| o.resolved.as_ref()),
+        Some(CommandDataOptionValue::String(action)) if action == "clear"
+    );
+
+    if clear {
+        let cleared = runtime.api.cache.clear().await;
+
+        // The other processes clear theirs when they see the new time.
+        let result = match runtime.storage.runtime_flags().await {
+            Ok(mut flags) => {
+                flags.cache_cleared = Timestamp::now().unix_timestamp();
+                runtime.storage.set_runtime_flags(&flags).await
+            }
+            Err(e) => Err(e),
+        };
+
+        if let Err(e) = result {
+            warn!("Unable to share the cache clear: {e}");
+            return tr!(locale, "admin-cache-cleared-here", count = cleared);
+        }
+
+        return tr!(
+            locale,
+            "admin-cache-cleared",
+            count = cleared,
+            seconds = RUNTIME_SYNC_INTERVAL.as_secs()
+        );
+    }
+
+    let stats = runtime.api.cache.stats().await;
+    format!(
+        "**{}**\n{}\n{}\n\n**{}**\n{}",
+        tr!(locale, "admin-cache-catalog"),
+        tr!(
+            locale,
+            "admin-cache-entries",
+            entries = stats.entries,
+            expired = stats.expired
+        ),
+        tr!(
+            locale,
+            "admin-cache-hits",
+            hits = stats.hits,
+            misses = stats.misses
+        ),
+        tr!(locale, "admin-cache-discord"),
+        tr!(
+            locale,
+            "admin-cache-discord-counts",
+            guilds = ctx.cache.guild_count(),
+            channels = ctx.cache.guild_channel_count(),
+            users = ctx.cache.user_count()
+        ),
+    )
+}
+
+async fn health(runtime: &Runtime<'_>, locale: &str) -> String {
+    let report = runtime.api.health.report().await;
+
+    let mut shards = report.shards.into_iter().collect::<Vec<_>>();
+    shards.sort();
+
+    let upstreams = report
+        .upstreams
+        .iter()
+        .map(|(upstream, at)| format!("{upstream:?}: <t:{at}:R>"))
+        .collect::<Vec<_>>();
+
+    format!(
+        "**{}**: {}\n**{}**: {}\n**{}**: {}\n**{}**\n{}",
+        tr!(locale, "admin-health-ready"),
+        match report.ready {
+            true => tr!(locale, "admin-yes"),
+            false => tr!(locale, "admin-no"),
+        },
+        tr!(locale, "admin-health-shards"),
+        shards
+            .iter()
+            .map(|(shard, up)| match up {
+                true => tr!(locale, "admin-shard-up", shard = *shard),
+                false => tr!(locale, "admin-shard-down", shard = *shard),
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        tr!(locale, "admin-health-token"),
+        report
+            .token_age_secs
+            .map(|secs| tr!(locale, "admin-token-age", minutes = secs / 60))
+            .unwrap_or(tr!(locale, "admin-token-never")),
+        tr!(locale, "admin-health-upstreams"),
+        match upstreams.is_empty() {
+            true => tr!(locale, "admin-none-yet"),
+            false => upstreams.join("\n"),
+        }
+    )
+}
+
+async fn maintenance(enabled: bool, runtime: &Runtime<'_>, locale: &str) -> String {
+    runtime.maintenance.store(enabled, Ordering::SeqCst);
+
+    let result = match runtime.storage.runtime_flags().await {
+        Ok(mut flags) => {
+            flags.maintenance = enabled;
+            runtime.storage.set_runtime_flags(&flags).await
+        }
+        Err(e) => Err(e),
+    };
+
+    if let Err(e) = result {
+        warn!("Unable to share maintenance mode: {e}");
+        return tr!(locale, "admin-maintenance-here");
+    }
+
+    match enabled {
+        true => tr!(
+            locale,
+            "admin-maintenance-on",
+            seconds = RUNTIME_SYNC_INTERVAL.as_secs()
+        ),
+        false => tr!(
+            locale,
+            "admin-maintenance-off",
+            seconds = RUNTIME_SYNC_INTERVAL.as_secs()
+        ),
+    }
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    ctx: &Context,
+    user_id: u64,
+    runtime: Runtime<'_>,
+    locale: &str,
+) -> String {
+    if !is_admin(ctx, user_id, runtime.config).await {
+        return tr!(locale, "admin-owner-only");
+    }
+
+    let Some(subcommand) = options.first() else {
+        return tr!(locale, "admin-pick");
+    };
+
+    info!("Admin {user_id} ran {}", subcommand.name);
+
+    match subcommand.name.as_str() {
+        "token" => {
+            let client = runtime.api.client.read().await.clone();
+            match updater::refresh_token(&client, &runtime.api.developer_token, &runtime.api.health)
+                .await
+            {
+                true => tr!(locale, "admin-token-refreshed"),
+                false => tr!(locale, "admin-token-failed"),
+            }
+        }
+        "cache" => cache(&subcommand.options, ctx, &runtime, locale).await,
+        "reload" => {
+            let config = Config::reload();
+            let reply = tr!(
+                locale,
+                "admin-reloaded",
+                templates = config.presence.templates.len(),
+                admins = config.admins.len()
+            );
+            *runtime.config.write().await = config;
+            reply
+        }
+        "health" => health(&runtime, locale).await,
+        "maintenance" => {
+            let enabled = matches!(
+                subcommand.options.first().and_then(|o| o.resolved.as_ref()),
+                Some(CommandDataOptionValue::Boolean(true))
+            );
+            maintenance(enabled, &runtime, locale).await
+        }
+        "commands" => match crate::commands::sync(&ctx.http, runtime.storage, true).await {
+            Ok(()) => tr!(locale, "admin-commands-registered"),
+            Err(why) => tr!(locale, "admin-commands-failed", error = why.to_string()),
+        },
+        _ => tr!(locale, "admin-pick"),
+    }
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("admin")
+        .description("Bot owner tools")
+        .default_member_permissions(Permissions::ADMINISTRATOR)
+        .create_option(|option| {
+            option
+                .name("token")
+                .description("Force a developer token refresh")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("cache")
+                .description("Inspect or clear the caches")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|action| {
+                    action
+                        .name("action")
+                        .description("What to do with the caches")
+                        .kind(CommandOptionType::String)
+                        .add_string_choice("Inspect", "inspect")
+                        .add_string_choice("Clear", "clear")
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("reload")
+                .description("Reload the configuration from the environment")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("health")
+                .description("Show shard, token and upstream health")
+                .kind(CommandOptionType::SubCommand)
+        })
+        .create_option(|option| {
+            option
+                .name("maintenance")
+                .description("Pause or resume auto-conversion")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|enabled| {
+                    enabled
+                        .name("enabled")
+                        .description("Whether maintenance mode is on")
+                        .kind(CommandOptionType::Boolean)
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("commands")
+                .description("Re-register the global slash commands")
+                .kind(CommandOptionType::SubCommand)
+        })
+}
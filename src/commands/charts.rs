@@ -0,0 +1,419 @@
+use reqwest::{Method, Url};
+use serde_json::Value;
+use serenity::builder::{CreateApplicationCommand, CreateComponents, CreateEmbed};
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use thiserror::Error;
+
+use super::{paging_buttons, Hint};
+use crate::{api::AppleMusicApi, cider_links::LinkButtons, tr, util, ValuePath};
+
+const DEFAULT_STOREFRONT: &str = "us";
+const PAGE_SIZE: usize = 10;
+// Embed descriptions take 4096 characters, long names or link bases end a page early.
+const MAX_PAGE_LEN: usize = 4096;
+// Plenty for a title, short enough that ten entries with their links fit on a page.
+const MAX_NAME_LEN: usize = 80;
+// What apple hands out in one request, enough for a top 50.
+const CHART_SIZE: usize = 50;
+
+/// Chart types as the catalog names them, with what we call them in the command.
+const KINDS: &[(&str, &str)] = &[
+    ("songs", "Songs"),
+    ("albums", "Albums"),
+    ("playlists", "Playlists"),
+    ("music-videos", "Music videos"),
+];
+
+#[derive(Error, Debug)]
+pub enum ChartsError {
+    #[error("not a storefront")]
+    InvalidStorefront,
+    #[error("could not find the genre")]
+    GenreNotFound,
+    #[error("chart is empty")]
+    Empty,
+    #[error("request failed")]
+    RequestError(#[from] reqwest::Error),
+}
+
+impl Hint for ChartsError {
+    fn id(&self) -> &'static str {
+        match self {
+            ChartsError::InvalidStorefront => "charts-invalid-storefront",
+            ChartsError::GenreNotFound => "charts-genre-not-found",
+            ChartsError::Empty => "charts-empty",
+            ChartsError::RequestError(_) => "charts-request",
+        }
+    }
+}
+
+pub struct Page {
+    pub embed: CreateEmbed,
+    storefront: String,
+    kind: String,
+    genre: Option<String>,
+    page: usize,
+    pages: usize,
+}
+
+impl Page {
+    pub fn buttons<'a>(
+        &self,
+        c: &'a mut CreateComponents,
+        locale: &str,
+    ) -> &'a mut CreateComponents {
+        paging_buttons(c, locale, self.page, self.pages, |page| {
+            custom_id(&self.storefront, &self.kind, self.genre.as_deref(), page)
+        })
+    }
+}
+
+fn custom_id(storefront: &str, kind: &str, genre: Option<&str>, page: usize) -> String {
+    format!(
+        "charts:{storefront}:{kind}:{}:{page}",
+        genre.unwrap_or_default()
+    )
+}
+
+/// Storefront, chart type, genre and page packed into a [`custom_id`].
+fn from_custom_id(custom_id: &str) -> Option<(&str, &str, Option<&str>, usize)> {
+    let mut parts = custom_id.strip_prefix("charts:")?.split(':');
+
+    let storefront = parts.next()?;
+    let kind = parts.next()?;
+    let genre = parts.next().filter(|genre| !genre.is_empty());
+    let page = parts.next()?.parse().ok()?;
+
+    Some((storefront, kind, genre, page))
+}
+
+/// Genres of a storefront as ids and names.
+async fn genres(
+    api: &AppleMusicApi,
+    storefront: &str,
+) -> Result<Vec<(String, String)>, reqwest::Error> {
+    let resp = api
+        .request_endpoint(Method::GET, &format!("v1/catalog/{storefront}/genres"))
+        .await?;
+
+    let Some(Value::Array(genres)) = resp.get_value_by_path("data") else {
+        return Ok(Vec::new());
+    };
+
+    Ok(genres
+        .iter()
+        .filter_map(|genre| {
+            Some((
+                genre.get_value_by_path("id")?.as_str()?.to_string(),
+                genre
+                    .get_value_by_path("attributes.name")?
+                    .as_str()?
+                    .to_string(),
+            ))
+        })
+        .collect())
+}
+
+/// Genre names in a storefront containing `partial`, for autocomplete.
+pub async fn genre_names(
+    api: &AppleMusicApi,
+    storefront: Option<&str>,
+    partial: &str,
+) -> Result<Vec<String>, reqwest::Error> {
+    let partial = partial.to_lowercase();
+
+    Ok(genres(api, storefront.unwrap_or(DEFAULT_STOREFRONT))
+        .await?
+        .into_iter()
+        .map(|(_, name)| name)
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .collect())
+}
+
+/// One entry of the chart, its name linked the way the guild's buttons are.
+fn entry(rank: usize, item: &Value, links: &LinkButtons) -> Option<String> {
+    let attribute = |name: &str| {
+        item.get_value_by_path(&format!("attributes.{name}"))
+            .and_then(|value| value.as_str().map(str::to_string))
+    };
+
+    let name = util::snippet(&attribute("name")?, MAX_NAME_LEN);
+    let url = attribute("url")?;
+
+    let mut line = format!(
+        "**{rank}.** {}",
+        util::masked_link(&name, &links.text_url(&url))
+    );
+    // Playlists are made by a curator rather than an artist.
+    if let Some(by) = attribute("artistName").or_else(|| attribute("curatorName")) {
+        line.push_str(&format!(" - {}", util::snippet(&by, MAX_NAME_LEN)));
+    }
+
+    Some(line)
+}
+
+/// Splits the entries into pages of at most [`PAGE_SIZE`] that fit in an embed description.
+fn paginate(entries: &[String]) -> Vec<String> {
+    let mut pages: Vec<(String, usize)> = Vec::new();
+
+    for entry in entries {
+        match pages.last_mut() {
+            Some((page, count))
+                if *count < PAGE_SIZE
+                    && page.chars().count() + 1 + entry.chars().count() <= MAX_PAGE_LEN =>
+            {
+                page.push('\n');
+                page.push_str(entry);
+                *count += 1;
+            }
+            _ => pages.push((entry.chars().take(MAX_PAGE_LEN).collect(), 1)),
+        }
+    }
+
+    pages.into_iter().map(|(page, _)| page).collect()
+}
+
+async fn page(
+    api: &AppleMusicApi,
+    storefront: &str,
+    kind: &str,
+    genre: Option<&str>,
+    page: usize,
+    locale: &str,
+    links: &LinkButtons,
+) -> Result<Page, ChartsError> {
+    let mut query = Url::parse("https://api.music.apple.com").unwrap();
+    query
+        .query_pairs_mut()
+        .append_pair("types", kind)
+        .append_pair("limit", &CHART_SIZE.to_string());
+    if let Some(genre) = genre {
+        query.query_pairs_mut().append_pair("genre", genre);
+    }
+
+    let resp = api
+        .request_endpoint(
+            Method::GET,
+            &format!(
+                "v1/catalog/{storefront}/charts?{}",
+                query.query().unwrap_or_default()
+            ),
+        )
+        .await?;
+
+    // A storefront that doesn't exist answers with errors instead of charts.
+    if resp.get("errors").is_some() {
+        return Err(ChartsError::InvalidStorefront);
+    }
+
+    let chart = resp
+        .get_value_by_path(&format!("results.{kind}.0"))
+        .ok_or(ChartsError::Empty)?;
+
+    let entries = match chart.get_value_by_path("data") {
+        Some(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| entry(index + 1, item, links))
+            .collect::<Vec<_>>(),
+        _ => Vec::new(),
+    };
+
+    let descriptions = paginate(&entries);
+    if descriptions.is_empty() {
+        return Err(ChartsError::Empty);
+    }
+
+    let pages = descriptions.len();
+    let page = page.min(pages - 1);
+
+    let name = chart
+        .get_value_by_path("name")
+        .and_then(|name| name.as_str().map(str::to_string))
+        .unwrap_or_else(|| tr!(locale, "charts-title"));
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(format!("{name} • {}", storefront.to_uppercase()))
+        .description(&descriptions[page])
+        .footer(|f| f.text(tr!(locale, "charts-page", page = page + 1, pages = pages)));
+
+    Ok(Page {
+        embed,
+        storefront: storefront.to_string(),
+        kind: kind.to_string(),
+        genre: genre.map(str::to_string),
+        page,
+        pages,
+    })
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    api: &AppleMusicApi,
+    locale: &str,
+    links: &LinkButtons,
+) -> Result<Page, ChartsError> {
+    let mut storefront = DEFAULT_STOREFRONT.to_string();
+    let mut kind = KINDS[0].0;
+    let mut genre = None;
+
+    for option in options {
+        let Some(CommandDataOptionValue::String(value)) = &option.resolved else {
+            continue;
+        };
+
+        match option.name.as_str() {
+            "storefront" => storefront = value.trim().to_lowercase(),
+            "type" => {
+                kind = KINDS
+                    .iter()
+                    .map(|(kind, _)| *kind)
+                    .find(|kind| kind == value)
+                    .unwrap_or(kind)
+            }
+            "genre" => genre = Some(value.trim()),
+            _ => {}
+        }
+    }
+
+    if !util::is_storefront(&storefront) {
+        return Err(ChartsError::InvalidStorefront);
+    }
+
+    let genre = match genre.filter(|genre| !genre.is_empty()) {
+        Some(genre) => Some(
+            genres(api, &storefront)
+                .await?
+                .into_iter()
+                .find(|(id, name)| name.eq_ignore_ascii_case(genre) || id == genre)
+                .map(|(id, _)| id)
+                .ok_or(ChartsError::GenreNotFound)?,
+        ),
+        None => None,
+    };
+
+    page(api, &storefront, kind, genre.as_deref(), 0, locale, links).await
+}
+
+/// Handles the paging buttons, returns `None` if the custom id isn't ours.
+pub async fn turn(
+    custom_id: &str,
+    api: &AppleMusicApi,
+    locale: &str,
+    links: &LinkButtons,
+) -> Option<Result<Page, ChartsError>> {
+    let (storefront, kind, genre, page_number) = from_custom_id(custom_id)?;
+    Some(page(api, storefront, kind, genre, page_number, locale, links).await)
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("charts")
+        .description("Show the Apple Music charts")
+        .create_option(|option| {
+            option
+                .name("storefront")
+                .description("Country code of the charts, e.g. us or de")
+                .kind(CommandOptionType::String)
+                .min_length(2)
+                .max_length(2)
+                .required(false)
+        })
+        .create_option(|option| {
+            option
+                .name("type")
+                .description("What to rank, songs by default")
+                .kind(CommandOptionType::String)
+                .required(false);
+
+            for (kind, name) in KINDS {
+                option.add_string_choice(name, kind);
+            }
+            option
+        })
+        .create_option(|option| {
+            option
+                .name("genre")
+                .description("Only rank this genre")
+                .kind(CommandOptionType::String)
+                .required(false)
+                .set_autocomplete(true)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cider_links::{self, Action};
+
+    #[test]
+    fn custom_id_round_trip() {
+        assert_eq!(
+            from_custom_id(&custom_id("de", "music-videos", Some("20"), 4)),
+            Some(("de", "music-videos", Some("20"), 4))
+        );
+        assert_eq!(
+            from_custom_id(&custom_id("us", "songs", None, 0)),
+            Some(("us", "songs", None, 0))
+        );
+        assert_eq!(from_custom_id("charts:us:songs:"), None);
+        assert_eq!(from_custom_id("history:0::::"), None);
+    }
+
+    #[test]
+    fn entries() {
+        let url =
+            "https://music.apple.com/us/playlist/todays-hits/pl.f4d106fed2bd41149aaacabb233eb5eb";
+        let playlist = serde_json::json!({
+            "attributes": { "name": "Today's Hits", "curatorName": "Apple Music Hits", "url": url }
+        });
+
+        let none = LinkButtons {
+            play: false,
+            view: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            entry(3, &playlist, &LinkButtons::default()).unwrap(),
+            format!(
+                "**3.** [Today's Hits]({}) - Apple Music Hits",
+                cider_links::web(url, Action::Play)
+            )
+        );
+        assert_eq!(
+            entry(3, &playlist, &none).unwrap(),
+            format!("**3.** [Today's Hits]({url}) - Apple Music Hits")
+        );
+
+        let long = serde_json::json!({
+            "attributes": { "name": "word ".repeat(100), "artistName": "Someone", "url": url }
+        });
+        let line = entry(1, &long, &none).unwrap();
+        assert!(line.chars().count() < MAX_NAME_LEN + url.len() + 30);
+
+        assert_eq!(
+            entry(1, &serde_json::json!({ "attributes": {} }), &none),
+            None
+        );
+    }
+
+    #[test]
+    fn pages_fit_in_an_embed() {
+        let short = vec!["entry".to_string(); 25];
+        let pages = paginate(&short);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[2], ["entry"; 5].join("\n"));
+
+        let long = vec!["x".repeat(1500); 10];
+        let pages = paginate(&long);
+        assert_eq!(pages.len(), 5);
+        assert!(pages
+            .iter()
+            .all(|page| page.chars().count() <= MAX_PAGE_LEN));
+    }
+}
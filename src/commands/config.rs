@@ -0,0 +1,280 @@
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::prelude::ChannelType;
+use serenity::model::{Permissions, Timestamp};
+
+use crate::cider_links::{self, LinkScheme};
+use crate::conversion::EmbedField;
+use crate::storage::{Storage, StorageError};
+use crate::{i18n, tr};
+
+async fn digest(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let mut config = storage.guild_config(guild_id).await?;
+
+    let channel = options.iter().find_map(|option| match &option.resolved {
+        Some(CommandDataOptionValue::Channel(channel)) => Some(channel.id.0),
+        _ => None,
+    });
+
+    let reply = match channel {
+        Some(channel) => {
+            // Start counting the week from now, instead of posting an empty recap right away.
+            if config.digest_channel.is_none() {
+                config.last_digest = Timestamp::now().unix_timestamp();
+            }
+            config.digest_channel = Some(channel);
+            tr!(
+                locale,
+                "config-digest-enabled",
+                channel = format!("<#{channel}>")
+            )
+        }
+        None => {
+            config.digest_channel = None;
+            tr!(locale, "config-digest-disabled")
+        }
+    };
+
+    storage.set_guild_config(guild_id, &config).await?;
+    Ok(reply)
+}
+
+async fn language(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let mut config = storage.guild_config(guild_id).await?;
+
+    config.locale = options.iter().find_map(|option| match &option.resolved {
+        Some(CommandDataOptionValue::String(locale)) => Some(i18n::resolve(locale).to_string()),
+        _ => None,
+    });
+
+    storage.set_guild_config(guild_id, &config).await?;
+
+    // Answer in the language we just picked, so it's obvious what changed.
+    Ok(match &config.locale {
+        Some(picked) => tr!(
+            picked,
+            "config-language-set",
+            language = i18n::language(picked)
+        ),
+        None => tr!(locale, "config-language-reset"),
+    })
+}
+
+async fn fields(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let mut config = storage.guild_config(guild_id).await?;
+
+    let mut field = None;
+    let mut shown = true;
+    for option in options {
+        match (option.name.as_str(), option.resolved.as_ref()) {
+            ("field", Some(CommandDataOptionValue::String(name))) => {
+                field = EmbedField::ALL.into_iter().find(|field| field.id() == name)
+            }
+            ("shown", Some(CommandDataOptionValue::Boolean(value))) => shown = *value,
+            _ => {}
+        }
+    }
+
+    let Some(field) = field else {
+        return Ok(tr!(locale, "config-pick"));
+    };
+
+    config.hidden_fields.retain(|hidden| *hidden != field);
+    if !shown {
+        config.hidden_fields.push(field);
+    }
+
+    storage.set_guild_config(guild_id, &config).await?;
+
+    let name = tr!(locale, field.id());
+    Ok(match shown {
+        true => tr!(locale, "config-field-shown", field = name),
+        false => tr!(locale, "config-field-hidden", field = name),
+    })
+}
+
+async fn links(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let mut config = storage.guild_config(guild_id).await?;
+    let links = &mut config.cider_links;
+
+    // Everything is optional, only touch what was picked.
+    for option in options {
+        match (option.name.as_str(), option.resolved.as_ref()) {
+            ("play", Some(CommandDataOptionValue::Boolean(value))) => links.play = *value,
+            ("view", Some(CommandDataOptionValue::Boolean(value))) => links.view = *value,
+            ("scheme", Some(CommandDataOptionValue::String(scheme))) => {
+                links.scheme = match scheme.as_str() {
+                    "cider" => LinkScheme::Cider,
+                    _ => LinkScheme::Web,
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let links = config.cider_links;
+    storage.set_guild_config(guild_id, &config).await?;
+
+    let buttons = [(links.play, "play-in-cider"), (links.view, "view-in-cider")]
+        .into_iter()
+        .filter(|(shown, _)| *shown)
+        .map(|(_, id)| tr!(locale, id))
+        .collect::<Vec<_>>();
+
+    let buttons = match buttons.is_empty() {
+        true => tr!(locale, "config-links-none"),
+        false => tr!(locale, "config-links-buttons", buttons = buttons.join(", ")),
+    };
+    let scheme = match links.scheme {
+        LinkScheme::Web => tr!(
+            locale,
+            "config-links-scheme-web",
+            base = cider_links::BASE.as_str()
+        ),
+        LinkScheme::Cider => tr!(locale, "config-links-scheme-cider"),
+    };
+
+    Ok(format!("{buttons}\n{scheme}"))
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: Option<u64>,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let Some(guild_id) = guild_id else {
+        return Ok(tr!(locale, "config-guild-only"));
+    };
+
+    let Some(subcommand) = options.first() else {
+        return Ok(tr!(locale, "config-pick"));
+    };
+
+    match subcommand.name.as_str() {
+        "digest" => digest(&subcommand.options, storage, guild_id, locale).await,
+        "language" => language(&subcommand.options, storage, guild_id, locale).await,
+        "fields" => fields(&subcommand.options, storage, guild_id, locale).await,
+        "links" => links(&subcommand.options, storage, guild_id, locale).await,
+        _ => Ok(tr!(locale, "config-pick")),
+    }
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("config")
+        .description("Change how Cidar behaves in this server")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("digest")
+                .description("Post a weekly recap of shared music, leave empty to disable")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|channel| {
+                    channel
+                        .name("channel")
+                        .description("Channel to post the recap in")
+                        .kind(CommandOptionType::Channel)
+                        // Anything else, like voice channels or categories, can't take the post.
+                        .channel_types(&[ChannelType::Text, ChannelType::News])
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("language")
+                .description("Language for Cidar's messages, leave empty to use the server's")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|locale| {
+                    locale
+                        .name("locale")
+                        .description("The language Cidar should use")
+                        .kind(CommandOptionType::String)
+                        .required(false);
+
+                    for (code, name, _) in i18n::LOCALES {
+                        locale.add_string_choice(name, code);
+                    }
+                    locale
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("fields")
+                .description("Show or hide details on embeds")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|field| {
+                    field
+                        .name("field")
+                        .description("The detail to change")
+                        .kind(CommandOptionType::String)
+                        .required(true);
+
+                    for embed_field in EmbedField::ALL {
+                        field.add_string_choice(
+                            tr!(i18n::DEFAULT_LOCALE, embed_field.id()),
+                            embed_field.id(),
+                        );
+                    }
+                    field
+                })
+                .create_sub_option(|shown| {
+                    shown
+                        .name("shown")
+                        .description("Whether to show it")
+                        .kind(CommandOptionType::Boolean)
+                        .required(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("links")
+                .description("Pick the Cider buttons on embeds and how their links open")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|play| {
+                    play.name("play")
+                        .description("Show the Play in Cider button")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|view| {
+                    view.name("view")
+                        .description("Show the View in Cider button")
+                        .kind(CommandOptionType::Boolean)
+                        .required(false)
+                })
+                .create_sub_option(|scheme| {
+                    scheme
+                        .name("scheme")
+                        .description("Also add cider:// links that open the app directly")
+                        .kind(CommandOptionType::String)
+                        .required(false)
+                        .add_string_choice("Web redirect", "web")
+                        .add_string_choice("Cider app", "cider")
+                })
+        })
+}
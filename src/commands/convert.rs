@@ -1,4 +1,5 @@
 use thiserror::Error;
+use tracing::{info_span, Instrument};
 
 use regex::Regex;
 use serde_json::Value;
@@ -8,7 +9,8 @@ use serenity::{
     builder::CreateApplicationCommand, model::prelude::application_command::CommandDataOptionValue,
 };
 
-use crate::{api::AppleMusicApi, util, ValuePath};
+use super::Hint;
+use crate::{api::AppleMusicApi, health::Upstream, storage::Storage, ValuePath};
 
 #[derive(Error, Debug)]
 pub enum ConvertError {
@@ -24,6 +26,17 @@ pub enum ConvertError {
     RequestError(String),
 }
 
+impl Hint for ConvertError {
+    fn id(&self) -> &'static str {
+        match self {
+            ConvertError::InvalidInput | ConvertError::InvalidOption => "convert-invalid-input",
+            ConvertError::InvalidContent => "convert-invalid-content",
+            ConvertError::FailedConversion => "convert-failed",
+            ConvertError::RequestError(_) => "convert-request",
+        }
+    }
+}
+
 impl From<reqwest::Error> for ConvertError {
     fn from(value: reqwest::Error) -> Self {
         ConvertError::RequestError(value.to_string())
@@ -33,9 +46,10 @@ impl From<reqwest::Error> for ConvertError {
 pub async fn run(
     _options: &[CommandDataOption],
     token: &AppleMusicApi,
+    storage: &Storage,
     regex: &Regex,
 ) -> Result<String, ConvertError> {
-    let opt = match _options.get(0) {
+    let opt = match _options.first() {
         Some(o) => o.resolved.as_ref(),
         None => return Err(ConvertError::InvalidInput),
     };
@@ -54,14 +68,17 @@ pub async fn run(
                 str
             ))
             .send()
+            .instrument(info_span!("song_link"))
             .await?
             .json()
             .await?;
 
+        token.health.upstream_ok(Upstream::SongLink).await;
+
         Ok(
             match response.get_value_by_path("linksByPlatform.appleMusic.url") {
                 Some(url) => {
-                    let _ = util::increment_conversion().await;
+                    let _ = storage.increment_conversion().await;
                     url.as_str().unwrap().to_string()
                 }
                 None => return Err(ConvertError::FailedConversion),
@@ -0,0 +1,454 @@
+use reqwest::{Method, Url};
+use serde_json::Value;
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::Permissions;
+use serenity::utils::Colour;
+use thiserror::Error;
+
+use super::Hint;
+use crate::conversion::{EmbedInformation, MediaType};
+use crate::guild::Follow;
+use crate::storage::{Storage, StorageError};
+use crate::{api::AppleMusicApi, tr, util, ValuePath};
+
+// Where to look for the artist when the storefront option is left empty.
+const DEFAULT_STOREFRONT: &str = "us";
+// Every follow is a request each time the poller comes around.
+const MAX_FOLLOWS: usize = 25;
+
+#[derive(Error, Debug)]
+pub enum FollowError {
+    #[error("could not find the artist")]
+    NotFound,
+    #[error("not a storefront")]
+    InvalidStorefront,
+    #[error("request failed")]
+    RequestError(#[from] reqwest::Error),
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+impl Hint for FollowError {
+    fn id(&self) -> &'static str {
+        match self {
+            FollowError::NotFound => "follow-not-found",
+            FollowError::InvalidStorefront => "follow-invalid-storefront",
+            FollowError::RequestError(_) => "follow-request",
+            FollowError::Storage(err) => err.id(),
+        }
+    }
+}
+
+/// Artists in the catalog matching `term`, as ids and names.
+pub async fn search(
+    api: &AppleMusicApi,
+    storefront: &str,
+    term: &str,
+    limit: usize,
+) -> Result<Vec<(String, String)>, reqwest::Error> {
+    let mut search = Url::parse("https://api.music.apple.com").unwrap();
+    search
+        .query_pairs_mut()
+        .append_pair("term", term)
+        .append_pair("types", "artists")
+        .append_pair("limit", &limit.to_string());
+
+    let resp = api
+        .request_endpoint(
+            Method::GET,
+            &format!(
+                "v1/catalog/{storefront}/search?{}",
+                search.query().unwrap_or_default()
+            ),
+        )
+        .await?;
+
+    let Some(Value::Array(artists)) = resp.get_value_by_path("results.artists.data") else {
+        return Ok(Vec::new());
+    };
+
+    Ok(artists.iter().filter_map(id_and_name).collect())
+}
+
+fn id_and_name(artist: &Value) -> Option<(String, String)> {
+    Some((
+        artist.get_value_by_path("id")?.as_str()?.to_string(),
+        artist
+            .get_value_by_path("attributes.name")?
+            .as_str()?
+            .to_string(),
+    ))
+}
+
+/// The id and name of the artist with catalog id `id`, `None` if there is no such artist.
+async fn lookup(
+    api: &AppleMusicApi,
+    storefront: &str,
+    id: &str,
+) -> Result<Option<(String, String)>, reqwest::Error> {
+    Ok(api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/artists/{id}"),
+        )
+        .await?
+        .get_value_by_path("data.0")
+        .as_ref()
+        .and_then(id_and_name))
+}
+
+/// The album resource of an artist's newest release, `None` if they haven't released anything.
+pub async fn latest_release(
+    api: &AppleMusicApi,
+    storefront: &str,
+    artist_id: &str,
+) -> Result<Option<Value>, reqwest::Error> {
+    Ok(api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/artists/{artist_id}/view/latest-release"),
+        )
+        .await?
+        .get_value_by_path("data.0"))
+}
+
+/// What we post when a followed artist puts something new out.
+pub fn announcement(follow: &Follow, release: &Value, locale: &str) -> Option<EmbedInformation> {
+    let attribute = |name: &str| {
+        release
+            .get_value_by_path(&format!("attributes.{name}"))
+            .and_then(|value| value.as_str().map(str::to_string))
+    };
+
+    Some(EmbedInformation {
+        title: attribute("name")?,
+        description: tr!(locale, "new-release", artist = &follow.artist),
+        footer: tr!(locale, "new-release-footer"),
+        released: attribute("releaseDate").map(|date| util::discord_date(&date)),
+        artwork: util::wh(&attribute("artwork.url")?, 512, 512),
+        colour: attribute("artwork.bgColor")
+            .and_then(|hex| u32::from_str_radix(&hex, 16).ok())
+            .map(Colour::new),
+        url: attribute("url")?,
+        storefront: follow.storefront.clone(),
+        media_type: MediaType::Album,
+        catalog_id: release.get_value_by_path("id")?.as_str()?.to_string(),
+        artist: Some(follow.artist.clone()),
+        artist_id: Some(follow.artist_id.clone()),
+        ..Default::default()
+    })
+}
+
+/// A subcommand's string option, `None` when it was left empty.
+fn string_option<'a>(options: &'a [CommandDataOption], name: &str) -> Option<&'a str> {
+    options
+        .iter()
+        .find_map(|option| match &option.resolved {
+            Some(CommandDataOptionValue::String(value)) if option.name == name => {
+                Some(value.trim())
+            }
+            _ => None,
+        })
+        .filter(|value| !value.is_empty())
+}
+
+/// The storefront picked so far, for suggesting artists while `/follow artist` is filled in.
+pub fn storefront_option(options: &[CommandDataOption]) -> String {
+    options
+        .iter()
+        .find(|option| option.name == "storefront")
+        .and_then(|option| option.value.as_ref()?.as_str())
+        .filter(|storefront| util::is_storefront(storefront))
+        .map_or(DEFAULT_STOREFRONT.to_string(), str::to_lowercase)
+}
+
+async fn artist(
+    options: &[CommandDataOption],
+    api: &AppleMusicApi,
+    storage: &Storage,
+    guild_id: u64,
+    channel_id: u64,
+    locale: &str,
+) -> Result<String, FollowError> {
+    let channel = options
+        .iter()
+        .find_map(|option| match &option.resolved {
+            Some(CommandDataOptionValue::Channel(channel)) => Some(channel.id.0),
+            _ => None,
+        })
+        .unwrap_or(channel_id);
+
+    let storefront = string_option(options, "storefront")
+        .unwrap_or(DEFAULT_STOREFRONT)
+        .to_lowercase();
+    if !util::is_storefront(&storefront) {
+        return Err(FollowError::InvalidStorefront);
+    }
+
+    let Some(name) = string_option(options, "name") else {
+        return Err(FollowError::NotFound);
+    };
+
+    // Suggestions fill in the artist's id, a name typed without picking one gets searched.
+    let found = match name.chars().all(|c| c.is_ascii_digit()) {
+        true => lookup(api, &storefront, name).await?,
+        false => None,
+    };
+    let found = match found {
+        Some(found) => Some(found),
+        None => search(api, &storefront, name, 1).await?.into_iter().next(),
+    };
+
+    let Some((artist_id, artist)) = found else {
+        return Err(FollowError::NotFound);
+    };
+
+    let mut config = storage.guild_config(guild_id).await?;
+
+    if let Some(follow) = config
+        .follows
+        .iter_mut()
+        .find(|follow| follow.artist_id == artist_id && follow.channel == channel)
+    {
+        follow.artist = artist.clone();
+    } else {
+        if config.follows.len() >= MAX_FOLLOWS {
+            return Ok(tr!(locale, "follow-limit", count = MAX_FOLLOWS));
+        }
+
+        // Remember what's out right now, only releases after this get announced.
+        let last_release = latest_release(api, &storefront, &artist_id)
+            .await?
+            .and_then(|release| {
+                release
+                    .get_value_by_path("id")?
+                    .as_str()
+                    .map(str::to_string)
+            });
+
+        config.follows.push(Follow {
+            artist_id,
+            artist: artist.clone(),
+            storefront,
+            channel,
+            last_release,
+        });
+    }
+
+    storage.set_guild_config(guild_id, &config).await?;
+
+    Ok(tr!(
+        locale,
+        "follow-added",
+        artist = artist,
+        channel = format!("<#{channel}>")
+    ))
+}
+
+async fn remove(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<String, FollowError> {
+    let Some(name) = string_option(options, "name") else {
+        return Err(FollowError::NotFound);
+    };
+
+    let mut config = storage.guild_config(guild_id).await?;
+
+    let before = config.follows.len();
+    config
+        .follows
+        .retain(|follow| !follow.artist.eq_ignore_ascii_case(name));
+
+    if config.follows.len() == before {
+        return Ok(tr!(locale, "follow-not-following", artist = name));
+    }
+
+    storage.set_guild_config(guild_id, &config).await?;
+    Ok(tr!(locale, "follow-removed", artist = name))
+}
+
+async fn list(storage: &Storage, guild_id: u64, locale: &str) -> Result<String, FollowError> {
+    let config = storage.guild_config(guild_id).await?;
+
+    if config.follows.is_empty() {
+        return Ok(tr!(locale, "follow-none"));
+    }
+
+    let follows = config
+        .follows
+        .iter()
+        .map(|follow| {
+            tr!(
+                locale,
+                "follow-list-entry",
+                artist = follow.artist.as_str(),
+                channel = format!("<#{}>", follow.channel)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Ok(format!("**{}**\n{follows}", tr!(locale, "follow-list")))
+}
+
+/// Followed artists whose name contains `partial`, for removing them.
+pub async fn followed(
+    storage: &Storage,
+    guild_id: u64,
+    partial: &str,
+) -> Result<Vec<String>, StorageError> {
+    let partial = partial.to_lowercase();
+    let mut artists = storage
+        .guild_config(guild_id)
+        .await?
+        .follows
+        .into_iter()
+        .map(|follow| follow.artist)
+        .filter(|artist| artist.to_lowercase().contains(&partial))
+        .collect::<Vec<_>>();
+
+    artists.sort();
+    artists.dedup();
+    Ok(artists)
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    api: &AppleMusicApi,
+    storage: &Storage,
+    guild_id: Option<u64>,
+    channel_id: u64,
+    locale: &str,
+) -> Result<String, FollowError> {
+    let Some(guild_id) = guild_id else {
+        return Ok(tr!(locale, "follow-guild-only"));
+    };
+
+    let Some(subcommand) = options.first() else {
+        return Ok(tr!(locale, "follow-pick"));
+    };
+
+    match subcommand.name.as_str() {
+        "artist" => {
+            artist(
+                &subcommand.options,
+                api,
+                storage,
+                guild_id,
+                channel_id,
+                locale,
+            )
+            .await
+        }
+        "remove" => remove(&subcommand.options, storage, guild_id, locale).await,
+        "list" => list(storage, guild_id, locale).await,
+        _ => Ok(tr!(locale, "follow-pick")),
+    }
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("follow")
+        .description("Get new releases from artists posted in this server")
+        .dm_permission(false)
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .create_option(|option| {
+            option
+                .name("artist")
+                .description("Follow an artist")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|name| {
+                    name.name("name")
+                        .description("The artist to follow")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .set_autocomplete(true)
+                })
+                .create_sub_option(|channel| {
+                    channel
+                        .name("channel")
+                        .description("Channel to post new releases in, this one if left empty")
+                        .kind(CommandOptionType::Channel)
+                        .required(false)
+                })
+                .create_sub_option(|storefront| {
+                    storefront
+                        .name("storefront")
+                        .description(
+                            "Country code of the store to follow them in, us if left empty",
+                        )
+                        .kind(CommandOptionType::String)
+                        .min_length(2)
+                        .max_length(2)
+                        .required(false)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("remove")
+                .description("Stop following an artist")
+                .kind(CommandOptionType::SubCommand)
+                .create_sub_option(|name| {
+                    name.name("name")
+                        .description("The artist to stop following")
+                        .kind(CommandOptionType::String)
+                        .required(true)
+                        .set_autocomplete(true)
+                })
+        })
+        .create_option(|option| {
+            option
+                .name("list")
+                .description("Show the artists this server follows")
+                .kind(CommandOptionType::SubCommand)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LATEST_RELEASE: &str = include_str!("../../fixtures/catalog/latest-release.json");
+
+    #[test]
+    fn announcement_fixture() {
+        let follow = Follow {
+            artist_id: "159260351".to_string(),
+            artist: "Taylor Swift".to_string(),
+            storefront: "gb".to_string(),
+            channel: 1,
+            last_release: None,
+        };
+        let resp: Value = serde_json::from_str(LATEST_RELEASE).unwrap();
+        let release = resp.get_value_by_path("data.0").unwrap();
+
+        let information = announcement(&follow, &release, "en-US").unwrap();
+
+        assert_eq!(information.title, "THE TORTURED POETS DEPARTMENT");
+        assert_eq!(information.description, "New music from Taylor Swift");
+        assert_eq!(information.released.as_deref(), Some("<t:1713484800:D>"));
+        assert_eq!(
+            information.artwork,
+            "https://is1-ssl.mzstatic.com/image/thumb/Music211/v4/ttpd/512x512bb.jpg"
+        );
+        assert_eq!(information.colour, Some(Colour::new(0xe6e2db)));
+        assert_eq!(information.catalog_id, "1716859186");
+        assert_eq!(information.storefront, "gb");
+        assert_eq!(information.artist_id.as_deref(), Some("159260351"));
+
+        // Without artwork there is nothing to show.
+        let mut bare = release.clone();
+        bare["attributes"]
+            .as_object_mut()
+            .unwrap()
+            .remove("artwork");
+        assert!(announcement(&follow, &bare, "en-US").is_none());
+    }
+}
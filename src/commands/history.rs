@@ -0,0 +1,428 @@
+use std::cmp::Reverse;
+
+use serenity::builder::{CreateApplicationCommand, CreateComponents, CreateEmbed};
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::Timestamp;
+use thiserror::Error;
+
+use super::{paging_buttons, Hint};
+use crate::cache::TtlCache;
+use crate::conversion::{ConversionRecord, MediaType};
+use crate::i18n;
+use crate::ranking::ranked;
+use crate::storage::{Storage, StorageError};
+use crate::tr;
+use crate::util::{self, DAY};
+
+const PAGE_SIZE: usize = 10;
+// Embed descriptions take 4096 characters, long titles end a page early.
+const MAX_PAGE_LEN: usize = 4096;
+
+// Custom ids are capped at 100 characters. The rest of the filter takes at most 60 of them
+// (`history:`, a page, a user id, a type code, a day and the separators), see the tests.
+const MAX_ARTIST_LEN: usize = 40;
+
+#[derive(Error, Debug)]
+pub enum HistoryError {
+    #[error("not a date")]
+    InvalidDate,
+    #[error(transparent)]
+    Storage(#[from] StorageError),
+}
+
+impl Hint for HistoryError {
+    fn id(&self) -> &'static str {
+        match self {
+            HistoryError::InvalidDate => "history-invalid-date",
+            HistoryError::Storage(err) => err.id(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Filter {
+    user: Option<u64>,
+    media_type: Option<MediaType>,
+    /// Start of the day to show, in unix seconds.
+    day: Option<i64>,
+    artist: Option<String>,
+}
+
+pub struct Page {
+    pub embed: CreateEmbed,
+    filter: Filter,
+    page: usize,
+    pages: usize,
+}
+
+impl Filter {
+    fn from_options(options: &[CommandDataOption]) -> Result<Filter, HistoryError> {
+        let mut filter = Filter::default();
+
+        for option in options {
+            match (option.name.as_str(), option.resolved.as_ref()) {
+                ("user", Some(CommandDataOptionValue::User(user, _))) => {
+                    filter.user = Some(user.id.0)
+                }
+                ("type", Some(CommandDataOptionValue::String(kind))) => {
+                    filter.media_type = MediaType::from_code(kind)
+                }
+                ("date", Some(CommandDataOptionValue::String(date))) => {
+                    filter.day = Some(parse_day(date)?)
+                }
+                ("artist", Some(CommandDataOptionValue::String(artist))) => {
+                    filter.artist = Some(artist.chars().take(MAX_ARTIST_LEN).collect())
+                }
+                _ => {}
+            }
+        }
+
+        Ok(filter)
+    }
+
+    fn matches(&self, record: &ConversionRecord) -> bool {
+        if self.user.is_some_and(|user| record.author_id != user) {
+            return false;
+        }
+
+        if self
+            .media_type
+            .is_some_and(|media_type| record.media_type != media_type)
+        {
+            return false;
+        }
+
+        if self
+            .day
+            .is_some_and(|day| record.timestamp < day || record.timestamp >= day + DAY)
+        {
+            return false;
+        }
+
+        if let Some(artist) = &self.artist {
+            let artist = artist.to_lowercase();
+            if !record
+                .artist
+                .as_ref()
+                .is_some_and(|name| name.to_lowercase().contains(&artist))
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Packs the filter into a button custom id, so paging works without keeping any state.
+    fn custom_id(&self, page: usize) -> String {
+        format!(
+            "history:{page}:{}:{}:{}:{}",
+            self.user.map(|user| user.to_string()).unwrap_or_default(),
+            self.media_type
+                .map(|media_type| media_type.code())
+                .unwrap_or_default(),
+            self.day.map(|day| day.to_string()).unwrap_or_default(),
+            self.artist.clone().unwrap_or_default(),
+        )
+    }
+
+    fn from_custom_id(custom_id: &str) -> Option<(Filter, usize)> {
+        let mut parts = custom_id.strip_prefix("history:")?.splitn(5, ':');
+        let non_empty = |part: &str| (!part.is_empty()).then(|| part.to_string());
+
+        let page = parts.next()?.parse().ok()?;
+        let filter = Filter {
+            user: parts.next()?.parse().ok(),
+            media_type: MediaType::from_code(parts.next()?),
+            day: parts.next()?.parse().ok(),
+            artist: non_empty(parts.next()?),
+        };
+
+        Some((filter, page))
+    }
+}
+
+impl Page {
+    pub fn buttons<'a>(
+        &self,
+        c: &'a mut CreateComponents,
+        locale: &str,
+    ) -> &'a mut CreateComponents {
+        // Nothing to page through.
+        if self.pages == 0 {
+            return c;
+        }
+
+        paging_buttons(c, locale, self.page, self.pages, |page| {
+            self.filter.custom_id(page)
+        })
+    }
+}
+
+/// Start of a day written like 2023-07-14, in unix seconds.
+fn parse_day(date: &str) -> Result<i64, HistoryError> {
+    let date = date.trim();
+    if date.len() != 10 {
+        return Err(HistoryError::InvalidDate);
+    }
+
+    Timestamp::parse(&format!("{date}T00:00:00Z"))
+        .map(|day| day.unix_timestamp())
+        .map_err(|_| HistoryError::InvalidDate)
+}
+
+fn entry(record: &ConversionRecord, guild_id: u64, locale: &str) -> String {
+    let title = format!("**{}**", util::escape_markdown(&record.title));
+    let title = match &record.artist {
+        Some(artist) if record.media_type != MediaType::Artist => tr!(
+            locale,
+            "title-by",
+            title = title,
+            artist = util::escape_markdown(artist)
+        ),
+        _ => title,
+    };
+
+    // Records from before we kept track of the message can't link back to it.
+    let jump = match record.message_id {
+        0 => String::new(),
+        message_id => format!(
+            " • [{}](https://discord.com/channels/{guild_id}/{}/{message_id})",
+            tr!(locale, "history-jump"),
+            record.channel_id
+        ),
+    };
+
+    format!(
+        "<t:{}:d> {}{jump}",
+        record.timestamp,
+        tr!(
+            locale,
+            "history-entry",
+            title = title,
+            kind = tr!(locale, record.media_type.id()),
+            name = util::escape_markdown(&record.author_name)
+        )
+    )
+}
+
+/// Splits the entries into pages of at most [`PAGE_SIZE`] that fit in an embed description.
+fn paginate(entries: &[String]) -> Vec<String> {
+    let mut pages: Vec<(String, usize)> = Vec::new();
+
+    for entry in entries {
+        match pages.last_mut() {
+            Some((page, count))
+                if *count < PAGE_SIZE
+                    && page.chars().count() + 1 + entry.chars().count() <= MAX_PAGE_LEN =>
+            {
+                page.push('\n');
+                page.push_str(entry);
+                *count += 1;
+            }
+            _ => pages.push((entry.chars().take(MAX_PAGE_LEN).collect(), 1)),
+        }
+    }
+
+    pages.into_iter().map(|(page, _)| page).collect()
+}
+
+async fn page(
+    storage: &Storage,
+    guild_id: u64,
+    filter: Filter,
+    page: usize,
+    locale: &str,
+) -> Result<Page, StorageError> {
+    let mut records = storage
+        .conversions(Some(guild_id), filter.day.unwrap_or(0))
+        .await?
+        .into_iter()
+        .filter(|record| filter.matches(record))
+        .collect::<Vec<_>>();
+
+    records.sort_by_key(|record| Reverse(record.timestamp));
+
+    let entries = records
+        .iter()
+        .map(|record| entry(record, guild_id, locale))
+        .collect::<Vec<_>>();
+    let descriptions = paginate(&entries);
+
+    let pages = descriptions.len();
+    let page = page.min(pages.saturating_sub(1));
+
+    let mut embed = CreateEmbed::default();
+    embed.title(tr!(locale, "history-title"));
+    match descriptions.get(page) {
+        Some(description) => embed
+            .description(description)
+            .footer(|f| f.text(tr!(locale, "history-page", page = page + 1, pages = pages))),
+        None => embed.description(tr!(locale, "history-none")),
+    };
+
+    Ok(Page {
+        embed,
+        filter,
+        page,
+        pages,
+    })
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Result<Page, HistoryError> {
+    Ok(page(storage, guild_id, Filter::from_options(options)?, 0, locale).await?)
+}
+
+/// Handles the previous and next buttons, returns `None` if the custom id isn't ours.
+pub async fn turn(
+    custom_id: &str,
+    storage: &Storage,
+    guild_id: u64,
+    locale: &str,
+) -> Option<Result<Page, StorageError>> {
+    let (filter, page_number) = Filter::from_custom_id(custom_id)?;
+    Some(page(storage, guild_id, filter, page_number, locale).await)
+}
+
+/// Artists shared in the guild whose name contains `partial`, most shared first. The full
+/// list is cached per guild, autocomplete asks again on every keystroke.
+pub async fn artists(
+    storage: &Storage,
+    cache: &TtlCache<Vec<String>>,
+    guild_id: u64,
+    partial: &str,
+) -> Result<Vec<String>, StorageError> {
+    let artists = match cache.get(&guild_id.to_string()).await {
+        Some(artists) => artists,
+        None => {
+            let records = storage.conversions(Some(guild_id), 0).await?;
+            let artists = ranked(
+                records
+                    .iter()
+                    .filter_map(|record| record.artist.as_ref())
+                    .map(|artist| (artist.to_lowercase(), artist.clone())),
+            )
+            .into_iter()
+            .map(|(artist, _)| artist)
+            .collect::<Vec<_>>();
+
+            cache.insert(guild_id.to_string(), artists.clone()).await;
+            artists
+        }
+    };
+
+    let partial = partial.to_lowercase();
+    Ok(artists
+        .into_iter()
+        .filter(|artist| artist.to_lowercase().contains(&partial))
+        .collect())
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("history")
+        .description("Search the music shared in this server")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("user")
+                .description("Only show shares from this member")
+                .kind(CommandOptionType::User)
+                .required(false)
+        })
+        .create_option(|option| {
+            option
+                .name("artist")
+                .description("Only show shares by this artist")
+                .kind(CommandOptionType::String)
+                .set_autocomplete(true)
+                .required(false)
+        })
+        .create_option(|option| {
+            option
+                .name("type")
+                .description("Only show this kind of media")
+                .kind(CommandOptionType::String)
+                .required(false);
+
+            for media_type in MediaType::ALL {
+                option.add_string_choice(
+                    tr!(i18n::DEFAULT_LOCALE, media_type.id()),
+                    media_type.code(),
+                );
+            }
+            option
+        })
+        .create_option(|option| {
+            option
+                .name("date")
+                .description("Only show shares from this day, e.g. 2023-07-14")
+                .kind(CommandOptionType::String)
+                .min_length(10)
+                .max_length(10)
+                .required(false)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_id_round_trip() {
+        let filter = Filter {
+            user: Some(105_371_984_734_838_784),
+            media_type: Some(MediaType::MusicVideo),
+            day: Some(parse_day("2023-07-14").unwrap()),
+            artist: Some("AC/DC: Live".to_string()),
+        };
+
+        assert_eq!(
+            Filter::from_custom_id(&filter.custom_id(3)),
+            Some((filter, 3))
+        );
+    }
+
+    #[test]
+    fn custom_id_fits_worst_case() {
+        let filter = Filter {
+            user: Some(u64::MAX),
+            media_type: Some(MediaType::SocialProfile),
+            day: Some(parse_day("0001-01-01").unwrap()),
+            artist: Some("é".repeat(MAX_ARTIST_LEN)),
+        };
+
+        assert!(filter.custom_id(99_999).chars().count() <= 100);
+    }
+
+    #[test]
+    fn pages_fit_in_an_embed() {
+        let short = vec!["entry".to_string(); 25];
+        let pages = paginate(&short);
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[2], ["entry"; 5].join("\n"));
+
+        let long = vec!["x".repeat(1500); 10];
+        let pages = paginate(&long);
+        assert_eq!(pages.len(), 5);
+        assert!(pages
+            .iter()
+            .all(|page| page.chars().count() <= MAX_PAGE_LEN));
+    }
+
+    #[test]
+    fn dates_are_validated() {
+        assert_eq!(parse_day("1970-01-02").unwrap(), DAY);
+        assert!(parse_day("2023-07-14T10:00").is_err());
+        assert!(parse_day("14.07.2023").is_err());
+        assert!(parse_day("2023-13-01").is_err());
+    }
+}
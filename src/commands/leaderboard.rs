@@ -0,0 +1,110 @@
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::model::Timestamp;
+
+use crate::conversion::MediaType;
+use crate::ranking::{key, listed, ranked};
+use crate::storage::{Storage, StorageError};
+use crate::tr;
+use crate::util::DAY;
+
+const TOP_ENTRIES: usize = 10;
+
+pub async fn run(
+    options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: Option<u64>,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let Some(guild_id) = guild_id else {
+        return Ok(tr!(locale, "leaderboard-guild-only"));
+    };
+
+    let window = match options.first().and_then(|o| o.resolved.as_ref()) {
+        Some(CommandDataOptionValue::String(window)) => window.as_str(),
+        _ => "all",
+    };
+
+    let (since, title) = match window {
+        "week" => (
+            Timestamp::now().unix_timestamp() - 7 * DAY,
+            "leaderboard-week",
+        ),
+        "month" => (
+            Timestamp::now().unix_timestamp() - 30 * DAY,
+            "leaderboard-month",
+        ),
+        _ => (0, "leaderboard-all"),
+    };
+
+    let records = storage.conversions(Some(guild_id), since).await?;
+    let by = |name: &str, artist: &Option<String>| match artist {
+        Some(artist) => tr!(locale, "title-by", title = name, artist = artist.as_str()),
+        None => name.to_string(),
+    };
+
+    let sharers = ranked(
+        records
+            .iter()
+            .filter(|record| record.author_id != 0)
+            .map(|record| (record.author_id.to_string(), record.author_name.clone())),
+    );
+
+    let tracks = ranked(
+        records
+            .iter()
+            .filter(|record| record.media_type == MediaType::Song)
+            .map(|record| {
+                (
+                    key(&record.catalog_id, &record.title),
+                    by(&record.title, &record.artist),
+                )
+            }),
+    );
+
+    let albums = ranked(records.iter().filter_map(|record| {
+        let album = record.album.as_ref()?;
+        Some((key(&record.album_id, album), by(album, &record.artist)))
+    }));
+
+    let artists = ranked(records.iter().filter_map(|record| {
+        let artist = record.artist.as_ref()?;
+        Some((key(&record.artist_id, artist), artist.clone()))
+    }));
+
+    let mut reply = format!("**{}**", tr!(locale, title));
+    for (title, ranked) in [
+        ("leaderboard-sharers", sharers),
+        ("leaderboard-tracks", tracks),
+        ("leaderboard-albums", albums),
+        ("leaderboard-artists", artists),
+    ] {
+        reply.push_str(&format!(
+            "\n\n**{}**\n{}",
+            tr!(locale, title),
+            listed(&ranked, TOP_ENTRIES).unwrap_or_else(|| tr!(locale, "nothing-shared"))
+        ));
+    }
+
+    Ok(reply)
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("leaderboard")
+        .description("See who shares the most music and what gets shared the most")
+        .dm_permission(false)
+        .create_option(|option| {
+            option
+                .name("window")
+                .description("Time window to rank")
+                .kind(CommandOptionType::String)
+                .add_string_choice("Weekly", "week")
+                .add_string_choice("Monthly", "month")
+                .add_string_choice("All time", "all")
+                .required(false)
+        })
+}
@@ -0,0 +1,291 @@
+use log::*;
+use reqwest::{Method, Url};
+use serenity::builder::{CreateApplicationCommand, CreateComponents, CreateEmbed};
+use serenity::model::prelude::command::CommandOptionType;
+use serenity::model::prelude::interaction::application_command::{
+    CommandDataOption, CommandDataOptionValue,
+};
+use serenity::utils::Colour;
+use thiserror::Error;
+
+use super::{paging_buttons, Hint};
+use crate::{api::AppleMusicApi, lyrics, tr, util, ValuePath};
+
+const PAGE_LINES: usize = 15;
+// Only ever show a snippet, we aren't a lyrics site.
+const PAGE_CHARS: usize = 1000;
+// Searches don't come with a storefront, so look in the biggest one.
+const SEARCH_STOREFRONT: &str = "us";
+
+#[derive(Error, Debug)]
+pub enum LyricsError {
+    #[error("did not input a song")]
+    InvalidInput,
+    #[error("could not find the song")]
+    NotFound,
+    #[error("song has no lyrics")]
+    Unavailable,
+    #[error("request failed")]
+    RequestError(#[from] reqwest::Error),
+}
+
+impl Hint for LyricsError {
+    fn id(&self) -> &'static str {
+        match self {
+            LyricsError::InvalidInput => "lyrics-invalid-input",
+            LyricsError::NotFound => "lyrics-not-found",
+            LyricsError::Unavailable => "lyrics-unavailable",
+            LyricsError::RequestError(_) => "lyrics-request",
+        }
+    }
+}
+
+pub struct Page {
+    pub embed: CreateEmbed,
+    storefront: String,
+    id: String,
+    page: usize,
+    pages: usize,
+}
+
+impl Page {
+    pub fn buttons<'a>(
+        &self,
+        c: &'a mut CreateComponents,
+        locale: &str,
+    ) -> &'a mut CreateComponents {
+        paging_buttons(c, locale, self.page, self.pages, |page| {
+            format!("lyrics:{}:{}:{page}", self.storefront, self.id)
+        })
+    }
+}
+
+/// The id of the button we put on song embeds, see [`turn`].
+pub fn button_id(storefront: &str, id: &str) -> String {
+    format!("lyrics:{storefront}:{id}")
+}
+
+/// Finds the storefront and id of a song from an apple music link, or by searching for it.
+async fn find_song(api: &AppleMusicApi, query: &str) -> Result<(String, String), LyricsError> {
+    if let Ok(url) = Url::parse(query) {
+        let segments = url
+            .path_segments()
+            .map(|segments| segments.collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let song = url
+            .query_pairs()
+            .find(|(key, _)| key == "i")
+            .map(|(_, id)| id.to_string())
+            .or_else(|| match segments.get(1) {
+                Some(&"song") => segments.last().map(|id| id.to_string()),
+                _ => None,
+            });
+
+        return match (segments.first(), song) {
+            (Some(storefront), Some(id)) => Ok((storefront.to_string(), id)),
+            _ => Err(LyricsError::NotFound),
+        };
+    }
+
+    let mut search = Url::parse("https://api.music.apple.com").unwrap();
+    search
+        .query_pairs_mut()
+        .append_pair("term", query)
+        .append_pair("types", "songs")
+        .append_pair("limit", "1");
+
+    let resp = api
+        .request_endpoint(
+            Method::GET,
+            &format!(
+                "v1/catalog/{SEARCH_STOREFRONT}/search?{}",
+                search.query().unwrap_or_default()
+            ),
+        )
+        .await?;
+
+    let id = resp
+        .get_value_by_path("results.songs.data.0.id")
+        .and_then(|id| id.as_str().map(str::to_string))
+        .ok_or(LyricsError::NotFound)?;
+
+    Ok((SEARCH_STOREFRONT.to_string(), id))
+}
+
+/// Splits the lines into pages that stay under both limits.
+fn paginate(lines: Vec<String>) -> Vec<String> {
+    let mut pages: Vec<Vec<String>> = vec![Vec::new()];
+
+    for line in lines {
+        let current = pages.last_mut().unwrap();
+        let length = current.iter().map(|line| line.len() + 1).sum::<usize>();
+
+        if !current.is_empty() && (current.len() >= PAGE_LINES || length + line.len() > PAGE_CHARS)
+        {
+            pages.push(vec![line]);
+        } else {
+            current.push(line);
+        }
+    }
+
+    pages.into_iter().map(|page| page.join("\n")).collect()
+}
+
+async fn page(
+    api: &AppleMusicApi,
+    storefront: &str,
+    id: &str,
+    page: usize,
+    locale: &str,
+) -> Result<Page, LyricsError> {
+    let song = api
+        .request_endpoint(Method::GET, &format!("v1/catalog/{storefront}/songs/{id}"))
+        .await?;
+
+    let attribute = |name: &str| {
+        song.get_value_by_path(&format!("data.0.attributes.{name}"))
+            .and_then(|value| value.as_str().map(str::to_string))
+    };
+
+    let Some(title) = attribute("name") else {
+        return Err(LyricsError::NotFound);
+    };
+
+    let ttml = api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/songs/{id}/lyrics"),
+        )
+        .await?
+        .get_value_by_path("data.0.attributes.ttml")
+        .and_then(|ttml| ttml.as_str().map(str::to_string))
+        .ok_or(LyricsError::Unavailable)?;
+
+    let lyrics = lyrics::parse(&ttml).map_err(|e| {
+        warn!("Unable to read the lyrics of {id}: {e}");
+        LyricsError::Unavailable
+    })?;
+
+    let mut lines = Vec::new();
+    let mut singer = None;
+
+    for line in lyrics.lines.iter().filter(|line| !line.text.is_empty()) {
+        // Call out who is singing when a duet switches voices.
+        if line.agent != singer {
+            singer = line.agent.clone();
+
+            let name = singer
+                .as_deref()
+                .and_then(|id| lyrics.agent(id)?.name.as_deref());
+            if let Some(name) = name {
+                lines.push(format!("**{name}**"));
+            }
+        }
+
+        lines.push(match line.begin {
+            Some(begin) if lyrics.synced() => {
+                format!("`{}` {}", util::milli_to_hhmmss(&begin), line.text)
+            }
+            _ => line.text.clone(),
+        });
+    }
+
+    if lines.is_empty() {
+        return Err(LyricsError::Unavailable);
+    }
+
+    let pages = paginate(lines);
+    let page = page.min(pages.len() - 1);
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(match attribute("artistName") {
+            Some(artist) => format!("{title} - {artist}"),
+            None => title,
+        })
+        .description(&pages[page])
+        .footer(|f| {
+            f.text(tr!(
+                locale,
+                "lyrics-page",
+                page = page + 1,
+                pages = pages.len()
+            ))
+        });
+
+    if let Some(url) = attribute("url") {
+        embed.url(url);
+    }
+
+    if let Some(artwork) = attribute("artwork.url") {
+        embed.thumbnail(util::wh(&artwork, 512, 512));
+    }
+
+    if let Some(colour) =
+        attribute("artwork.bgColor").and_then(|hex| u32::from_str_radix(&hex, 16).ok())
+    {
+        embed.colour(Colour::new(colour));
+    }
+
+    Ok(Page {
+        embed,
+        storefront: storefront.to_string(),
+        id: id.to_string(),
+        page,
+        pages: pages.len(),
+    })
+}
+
+pub async fn run(
+    options: &[CommandDataOption],
+    api: &AppleMusicApi,
+    locale: &str,
+) -> Result<Page, LyricsError> {
+    let query = options.iter().find_map(|option| match &option.resolved {
+        Some(CommandDataOptionValue::String(query)) => Some(query.trim()),
+        _ => None,
+    });
+
+    let Some(query) = query.filter(|query| !query.is_empty()) else {
+        return Err(LyricsError::InvalidInput);
+    };
+
+    let (storefront, id) = find_song(api, query).await?;
+    page(api, &storefront, &id, 0, locale).await
+}
+
+/// Handles the lyrics button on song embeds and the paging buttons, returns `None` if the
+/// custom id isn't ours. The bool is whether this opens the lyrics rather than turning a page.
+pub async fn turn(
+    custom_id: &str,
+    api: &AppleMusicApi,
+    locale: &str,
+) -> Option<(bool, Result<Page, LyricsError>)> {
+    let mut parts = custom_id.strip_prefix("lyrics:")?.split(':');
+
+    let storefront = parts.next()?;
+    let id = parts.next()?;
+    let page_number = parts.next().map(str::parse::<usize>);
+
+    match page_number {
+        None => Some((true, page(api, storefront, id, 0, locale).await)),
+        Some(Ok(page_number)) => {
+            Some((false, page(api, storefront, id, page_number, locale).await))
+        }
+        Some(Err(_)) => None,
+    }
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("lyrics")
+        .description("Look up the lyrics of a song")
+        .create_option(|option| {
+            option
+                .name("song")
+                .description("An Apple Music link or what to search for")
+                .kind(CommandOptionType::String)
+                .required(true)
+        })
+}
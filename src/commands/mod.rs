@@ -1,2 +1,812 @@
+use std::{future::Future, pin::Pin};
+
+use log::*;
+use serde_json::Value;
+use serenity::builder::{CreateApplicationCommand, CreateComponents, CreateEmbed};
+use serenity::http::Http;
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::prelude::command::Command;
+use serenity::model::prelude::interaction::application_command::{
+    ApplicationCommandInteraction, CommandDataOption,
+};
+use serenity::model::prelude::interaction::autocomplete::AutocompleteInteraction;
+use serenity::model::prelude::interaction::message_component::MessageComponentInteraction;
+use serenity::model::prelude::interaction::InteractionResponseType;
+use serenity::prelude::Context;
+use serenity::utils::Colour;
+
+use crate::{
+    alternates, i18n, similar,
+    storage::{Storage, StorageError},
+    tr, util, Handler,
+};
+
 pub mod about;
+pub mod admin;
+pub mod charts;
+pub mod config;
 pub mod convert;
+pub mod follow;
+pub mod history;
+pub mod leaderboard;
+pub mod lyrics;
+pub mod stats;
+
+/// Errors we show to users come with a hint on what to do about them. `id` names the
+/// catalog message for the title, the hint lives under `<id>-hint`.
+pub trait Hint {
+    fn id(&self) -> &'static str;
+}
+
+impl Hint for StorageError {
+    fn id(&self) -> &'static str {
+        match self {
+            StorageError::Database(_) => "storage-database",
+            StorageError::Io(_) | StorageError::Json(_) => "storage-internal",
+        }
+    }
+}
+
+/// What a command answers with, sent by editing the deferred interaction response.
+#[derive(Default)]
+pub struct Reply {
+    pub content: String,
+    pub embeds: Vec<CreateEmbed>,
+    pub components: Option<CreateComponents>,
+    /// Only show this reply to whoever ran the command, even if the command usually answers publicly.
+    pub ephemeral: bool,
+}
+
+impl Reply {
+    pub fn text(content: impl ToString) -> Reply {
+        Reply {
+            content: content.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn error(err: &impl Hint, locale: &str) -> Reply {
+        Reply {
+            embeds: vec![error_embed(err, locale)],
+            ephemeral: true,
+            ..Default::default()
+        }
+    }
+}
+
+fn error_embed(err: &impl Hint, locale: &str) -> CreateEmbed {
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(tr!(locale, err.id()))
+        .description(tr!(locale, &format!("{}-hint", err.id())))
+        .colour(Colour::RED);
+    embed
+}
+
+/// Previous and next buttons for paged replies, `custom_id` builds the id that opens a page.
+pub fn paging_buttons<'a>(
+    c: &'a mut CreateComponents,
+    locale: &str,
+    page: usize,
+    pages: usize,
+    custom_id: impl Fn(usize) -> String,
+) -> &'a mut CreateComponents {
+    c.create_action_row(|r| {
+        r.create_button(|b| {
+            b.label(tr!(locale, "paging-previous"))
+                .style(ButtonStyle::Secondary)
+                .custom_id(custom_id(page.saturating_sub(1)))
+                .disabled(page == 0)
+        })
+        .create_button(|b| {
+            b.label(tr!(locale, "paging-next"))
+                .style(ButtonStyle::Secondary)
+                .custom_id(custom_id(page + 1))
+                .disabled(page + 1 >= pages)
+        })
+    })
+}
+
+type Run = for<'a> fn(
+    &'a Handler,
+    &'a Context,
+    &'a ApplicationCommandInteraction,
+) -> Pin<Box<dyn Future<Output = Reply> + Send + 'a>>;
+
+/// Suggestions for the focused option, as the name shown and the value the command gets.
+type Autocomplete = for<'a> fn(
+    &'a Handler,
+    &'a AutocompleteInteraction,
+) -> Pin<Box<dyn Future<Output = Vec<(String, String)>> + Send + 'a>>;
+
+pub struct SlashCommand {
+    pub name: &'static str,
+    pub register: fn(&mut CreateApplicationCommand) -> &mut CreateApplicationCommand,
+    pub run: Run,
+    pub autocomplete: Option<Autocomplete>,
+    /// Only show the answer to whoever ran the command.
+    pub ephemeral: bool,
+}
+
+/// Every slash command the bot knows about. Adding one here is all it takes to register
+/// it with discord and route interactions to it.
+pub const COMMANDS: &[SlashCommand] = &[
+    SlashCommand {
+        name: "about",
+        register: about::register,
+        run: |_, _, command| {
+            Box::pin(async move { Reply::text(about::run(&command.data.options, &command.locale)) })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "convert",
+        register: convert::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                convert::run(
+                    &command.data.options,
+                    &handler.api,
+                    &handler.storage,
+                    &handler.url_regex,
+                )
+                .await
+                .map_or_else(|err| Reply::error(&err, &command.locale), Reply::text)
+            })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "stats",
+        register: stats::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                stats::run(
+                    &command.data.options,
+                    &handler.storage,
+                    command.guild_id.map(|id| id.0),
+                    handler.started,
+                    &command.locale,
+                )
+                .await
+                .map_or_else(|err| Reply::error(&err, &command.locale), Reply::text)
+            })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "leaderboard",
+        register: leaderboard::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                leaderboard::run(
+                    &command.data.options,
+                    &handler.storage,
+                    command.guild_id.map(|id| id.0),
+                    &command.locale,
+                )
+                .await
+                .map_or_else(|err| Reply::error(&err, &command.locale), Reply::text)
+            })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "history",
+        register: history::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                let Some(guild_id) = command.guild_id else {
+                    return Reply::text(tr!(&command.locale, "history-guild-only"));
+                };
+
+                match history::run(
+                    &command.data.options,
+                    &handler.storage,
+                    guild_id.0,
+                    &command.locale,
+                )
+                .await
+                {
+                    Ok(page) => {
+                        let mut components = CreateComponents::default();
+                        page.buttons(&mut components, &command.locale);
+
+                        Reply {
+                            embeds: vec![page.embed],
+                            components: Some(components),
+                            ..Default::default()
+                        }
+                    }
+                    Err(err) => Reply::error(&err, &command.locale),
+                }
+            })
+        },
+        autocomplete: Some(|handler, autocomplete| {
+            Box::pin(async move {
+                let (Some(guild_id), Some(partial)) =
+                    (autocomplete.guild_id, focused(&autocomplete.data.options))
+                else {
+                    return Vec::new();
+                };
+
+                history::artists(
+                    &handler.storage,
+                    &handler.shared_artists,
+                    guild_id.0,
+                    partial,
+                )
+                .await
+                .map(same)
+                .unwrap_or_else(|e| {
+                    warn!("Unable to suggest artists: {e}");
+                    Vec::new()
+                })
+            })
+        }),
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "lyrics",
+        register: lyrics::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                match lyrics::run(&command.data.options, &handler.api, &command.locale).await {
+                    Ok(page) => {
+                        let mut components = CreateComponents::default();
+                        page.buttons(&mut components, &command.locale);
+
+                        Reply {
+                            embeds: vec![page.embed],
+                            components: Some(components),
+                            ..Default::default()
+                        }
+                    }
+                    Err(err) => Reply::error(&err, &command.locale),
+                }
+            })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "charts",
+        register: charts::register,
+        run: |handler, ctx, command| {
+            Box::pin(async move {
+                let (config, _) = handler.guild_settings(ctx, command.guild_id).await;
+
+                match charts::run(
+                    &command.data.options,
+                    &handler.api,
+                    &command.locale,
+                    &config.cider_links,
+                )
+                .await
+                {
+                    Ok(page) => {
+                        let mut components = CreateComponents::default();
+                        page.buttons(&mut components, &command.locale);
+
+                        Reply {
+                            embeds: vec![page.embed],
+                            components: Some(components),
+                            ..Default::default()
+                        }
+                    }
+                    Err(err) => Reply::error(&err, &command.locale),
+                }
+            })
+        },
+        autocomplete: Some(|handler, autocomplete| {
+            Box::pin(async move {
+                let Some(partial) = focused(&autocomplete.data.options) else {
+                    return Vec::new();
+                };
+
+                // Genres differ between storefronts, suggest the ones of the storefront picked so far.
+                let storefront = autocomplete
+                    .data
+                    .options
+                    .iter()
+                    .find(|option| option.name == "storefront")
+                    .and_then(|option| option.value.as_ref()?.as_str())
+                    .filter(|storefront| util::is_storefront(storefront))
+                    .map(str::to_lowercase);
+
+                charts::genre_names(&handler.api, storefront.as_deref(), partial)
+                    .await
+                    .map(same)
+                    .unwrap_or_else(|e| {
+                        warn!("Unable to suggest genres: {e}");
+                        Vec::new()
+                    })
+            })
+        }),
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "config",
+        register: config::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                config::run(
+                    &command.data.options,
+                    &handler.storage,
+                    command.guild_id.map(|id| id.0),
+                    &command.locale,
+                )
+                .await
+                .map_or_else(|err| Reply::error(&err, &command.locale), Reply::text)
+            })
+        },
+        autocomplete: None,
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "follow",
+        register: follow::register,
+        run: |handler, _, command| {
+            Box::pin(async move {
+                follow::run(
+                    &command.data.options,
+                    &handler.api,
+                    &handler.storage,
+                    command.guild_id.map(|id| id.0),
+                    command.channel_id.0,
+                    &command.locale,
+                )
+                .await
+                .map_or_else(|err| Reply::error(&err, &command.locale), Reply::text)
+            })
+        },
+        autocomplete: Some(|handler, autocomplete| {
+            Box::pin(async move {
+                let (Some(guild_id), Some(subcommand), Some(partial)) = (
+                    autocomplete.guild_id,
+                    autocomplete.data.options.first(),
+                    focused(&autocomplete.data.options),
+                ) else {
+                    return Vec::new();
+                };
+
+                // Following searches the catalog, removing only offers what is followed.
+                if subcommand.name == "remove" {
+                    return follow::followed(&handler.storage, guild_id.0, partial)
+                        .await
+                        .map(same)
+                        .unwrap_or_else(|e| {
+                            warn!("Unable to suggest followed artists: {e}");
+                            Vec::new()
+                        });
+                }
+
+                if partial.trim().is_empty() {
+                    return Vec::new();
+                }
+
+                // The id goes to the command, so it follows exactly the artist that was picked.
+                let storefront = follow::storefront_option(&subcommand.options);
+                match follow::search(&handler.api, &storefront, partial, 10).await {
+                    Ok(artists) => artists.into_iter().map(|(id, name)| (name, id)).collect(),
+                    Err(e) => {
+                        warn!("Unable to suggest artists: {e}");
+                        Vec::new()
+                    }
+                }
+            })
+        }),
+        ephemeral: false,
+    },
+    SlashCommand {
+        name: "admin",
+        register: admin::register,
+        run: |handler, ctx, command| {
+            Box::pin(async move {
+                Reply::text(
+                    admin::run(
+                        &command.data.options,
+                        ctx,
+                        command.user.id.0,
+                        admin::Runtime {
+                            api: &handler.api,
+                            storage: &handler.storage,
+                            config: &handler.config,
+                            maintenance: &handler.maintenance,
+                        },
+                        &command.locale,
+                    )
+                    .await,
+                )
+            })
+        },
+        autocomplete: None,
+        ephemeral: true,
+    },
+];
+
+pub fn find(name: &str) -> Option<&'static SlashCommand> {
+    COMMANDS.iter().find(|command| command.name == name)
+}
+
+/// Suggestions that are sent to the command as they are shown.
+fn same(suggestions: Vec<String>) -> Vec<(String, String)> {
+    suggestions
+        .into_iter()
+        .map(|suggestion| (suggestion.clone(), suggestion))
+        .collect()
+}
+
+/// What the user typed so far in the option they are filling in.
+fn focused(options: &[CommandDataOption]) -> Option<&str> {
+    options.iter().find_map(|option| {
+        if option.focused {
+            option.value.as_ref()?.as_str()
+        } else {
+            focused(&option.options)
+        }
+    })
+}
+
+/// Defers the response so discord shows the command as thinking, then answers with whatever
+/// the command replied.
+pub async fn respond(handler: &Handler, ctx: &Context, command: &ApplicationCommandInteraction) {
+    let Some(slash) = find(&command.data.name) else {
+        warn!("Received unknown command {}", command.data.name);
+        return;
+    };
+
+    let deferred = if slash.ephemeral {
+        command.defer_ephemeral(&ctx.http).await
+    } else {
+        command.defer(&ctx.http).await
+    };
+
+    if let Err(why) = deferred {
+        warn!("Cannot defer slash command: {why}");
+        return;
+    }
+
+    let reply = (slash.run)(handler, ctx, command).await;
+
+    // The thinking message is already public, so an ephemeral reply has to replace it with a followup.
+    let result = if reply.ephemeral && !slash.ephemeral {
+        let _ = command
+            .delete_original_interaction_response(&ctx.http)
+            .await;
+
+        command
+            .create_followup_message(&ctx.http, |message| {
+                if let Some(components) = reply.components {
+                    message.set_components(components);
+                }
+                message
+                    .content(reply.content)
+                    .add_embeds(reply.embeds)
+                    .ephemeral(true)
+            })
+            .await
+            .map(|_| ())
+    } else {
+        command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                if let Some(components) = reply.components {
+                    response.set_components(components);
+                }
+                response.content(reply.content).set_embeds(reply.embeds)
+            })
+            .await
+            .map(|_| ())
+    };
+
+    if let Err(why) = result {
+        warn!("Cannot respond to slash command: {why}");
+    }
+}
+
+pub async fn autocomplete(
+    handler: &Handler,
+    ctx: &Context,
+    autocomplete: &AutocompleteInteraction,
+) {
+    let Some(suggest) = find(&autocomplete.data.name).and_then(|slash| slash.autocomplete) else {
+        return;
+    };
+
+    let suggestions = suggest(handler, autocomplete).await;
+
+    if let Err(why) = autocomplete
+        .create_autocomplete_response(&ctx.http, |response| {
+            // Discord only takes 25 suggestions, each up to 100 characters.
+            for (name, value) in suggestions.iter().take(25) {
+                response.add_string_choice(
+                    name.chars().take(100).collect::<String>(),
+                    value.chars().take(100).collect::<String>(),
+                );
+            }
+            response
+        })
+        .await
+    {
+        warn!("Cannot respond to autocomplete: {why}");
+    }
+}
+
+/// Routes buttons and select menus by the prefix of their custom id.
+pub async fn component(handler: &Handler, ctx: &Context, component: &MessageComponentInteraction) {
+    let custom_id = component.data.custom_id.as_str();
+
+    let result = if custom_id.starts_with("history:") {
+        let Some(guild_id) = component.guild_id else {
+            return;
+        };
+
+        match history::turn(custom_id, &handler.storage, guild_id.0, &component.locale).await {
+            Some(Ok(page)) => {
+                component
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|message| {
+                                message
+                                    .set_embed(page.embed.clone())
+                                    .components(|c| page.buttons(c, &component.locale))
+                            })
+                    })
+                    .await
+            }
+            Some(Err(err)) => component_error(ctx, component, &err).await,
+            None => return,
+        }
+    } else if custom_id.starts_with("lyrics:") {
+        match lyrics::turn(custom_id, &handler.api, &component.locale).await {
+            Some((opening, Ok(page))) => {
+                component
+                    .create_interaction_response(&ctx.http, |response| {
+                        // The button on a song embed opens the lyrics just for whoever pressed it,
+                        // paging through them after that updates that same message.
+                        let kind = match opening {
+                            true => InteractionResponseType::ChannelMessageWithSource,
+                            false => InteractionResponseType::UpdateMessage,
+                        };
+
+                        response.kind(kind).interaction_response_data(|message| {
+                            message
+                                .set_embed(page.embed.clone())
+                                .components(|c| page.buttons(c, &component.locale))
+                                .ephemeral(opening)
+                        })
+                    })
+                    .await
+            }
+            Some((_, Err(err))) => component_error(ctx, component, &err).await,
+            None => return,
+        }
+    } else if custom_id.starts_with("charts:") {
+        let (config, _) = handler.guild_settings(ctx, component.guild_id).await;
+
+        match charts::turn(
+            custom_id,
+            &handler.api,
+            &component.locale,
+            &config.cider_links,
+        )
+        .await
+        {
+            Some(Ok(page)) => {
+                component
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|message| {
+                                message
+                                    .set_embed(page.embed.clone())
+                                    .components(|c| page.buttons(c, &component.locale))
+                            })
+                    })
+                    .await
+            }
+            Some(Err(err)) => component_error(ctx, component, &err).await,
+            None => return,
+        }
+    } else if custom_id.starts_with("similar:") {
+        // A song takes a few requests, which can take longer than discord waits for an answer.
+        let deferred = component
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                    .interaction_response_data(|message| message.ephemeral(true))
+            })
+            .await;
+
+        if let Err(why) = deferred {
+            warn!("Cannot defer component: {why}");
+            return;
+        }
+
+        let (config, _) = handler.guild_settings(ctx, component.guild_id).await;
+
+        let embed = match similar::recommend(
+            custom_id,
+            &handler.api,
+            &handler.similar,
+            &component.locale,
+            &config.cider_links,
+        )
+        .await
+        {
+            Some(Ok(embed)) => embed,
+            Some(Err(err)) => error_embed(&err, &component.locale),
+            None => {
+                let _ = component
+                    .delete_original_interaction_response(&ctx.http)
+                    .await;
+                return;
+            }
+        };
+
+        component
+            .edit_original_interaction_response(&ctx.http, |response| response.set_embed(embed))
+            .await
+            .map(|_| ())
+    } else if custom_id.starts_with("alt:") {
+        let Some(song_id) = component.data.values.first() else {
+            return;
+        };
+
+        // The embed is in the guild's language, keep it that way.
+        let (config, locale) = handler.guild_settings(ctx, component.guild_id).await;
+
+        match alternates::swap(custom_id, song_id, &handler.api, ctx, &locale).await {
+            Some(Ok(version)) => {
+                component
+                    .create_interaction_response(&ctx.http, |response| {
+                        response
+                            .kind(InteractionResponseType::UpdateMessage)
+                            .interaction_response_data(|message| {
+                                message
+                                    .embed(|e| version.information.embed(e, &locale, &config))
+                                    .components(|c| {
+                                        version.components(c, &locale, &config.cider_links)
+                                    })
+                            })
+                    })
+                    .await
+            }
+            Some(Err(err)) => component_error(ctx, component, &err).await,
+            None => return,
+        }
+    } else {
+        warn!("Received unknown component {custom_id}");
+        return;
+    };
+
+    if let Err(why) = result {
+        warn!("Cannot respond to component: {why}");
+    }
+}
+
+async fn component_error(
+    ctx: &Context,
+    component: &MessageComponentInteraction,
+    err: &impl Hint,
+) -> serenity::Result<()> {
+    component
+        .create_interaction_response(&ctx.http, |response| {
+            response
+                .kind(InteractionResponseType::ChannelMessageWithSource)
+                .interaction_response_data(|message| {
+                    message
+                        .add_embed(error_embed(err, &component.locale))
+                        .ephemeral(true)
+                })
+        })
+        .await
+}
+
+/// Sorts object keys all the way down, builders are backed by hash maps so their key order
+/// changes between runs.
+fn canonical(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonical(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(values) => Value::Array(values.into_iter().map(canonical).collect()),
+        value => value,
+    }
+}
+
+/// The json we would send discord for every command, used to tell if anything changed.
+fn definitions() -> String {
+    let definitions = COMMANDS
+        .iter()
+        .map(|command| {
+            let mut builder = CreateApplicationCommand::default();
+            i18n::localize_command((command.register)(&mut builder));
+
+            canonical(Value::Object(
+                builder
+                    .0
+                    .into_iter()
+                    .map(|(key, value)| (key.to_string(), value))
+                    .collect(),
+            ))
+        })
+        .collect::<Vec<_>>();
+
+    Value::Array(definitions).to_string()
+}
+
+async fn overwrite_global(http: &Http) -> serenity::Result<()> {
+    Command::set_global_application_commands(http, |commands| {
+        for command in COMMANDS {
+            commands.create_application_command(|builder| {
+                i18n::localize_command((command.register)(builder))
+            });
+        }
+        commands
+    })
+    .await?;
+
+    Ok(())
+}
+
+/// Registers every command. Global commands are only overwritten when their definitions
+/// changed since the last time, unless `force` is set. Debug builds with `DEV_GUILD` set
+/// register to that guild instead, which updates instantly.
+pub async fn sync(http: &Http, storage: &Storage, force: bool) -> serenity::Result<()> {
+    #[cfg(debug_assertions)]
+    if let Some(guild) = std::env::var("DEV_GUILD")
+        .ok()
+        .and_then(|guild| guild.parse().ok())
+    {
+        info!("Registering commands to development guild {guild}");
+        serenity::model::id::GuildId(guild)
+            .set_application_commands(http, |commands| {
+                for command in COMMANDS {
+                    commands.create_application_command(|builder| {
+                        i18n::localize_command((command.register)(builder))
+                    });
+                }
+                commands
+            })
+            .await?;
+
+        return Ok(());
+    }
+
+    let definitions = definitions();
+
+    let unchanged = match storage.command_definitions().await {
+        Ok(stored) => stored.as_deref() == Some(definitions.as_str()),
+        Err(e) => {
+            warn!("Unable to read the registered command definitions: {e}");
+            false
+        }
+    };
+
+    if unchanged && !force {
+        info!("Global commands are up to date");
+        return Ok(());
+    }
+
+    info!("Overwriting global commands");
+    overwrite_global(http).await?;
+
+    if let Err(e) = storage.set_command_definitions(&definitions).await {
+        warn!("Unable to save the registered command definitions: {e}");
+    }
+
+    Ok(())
+}
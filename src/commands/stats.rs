@@ -0,0 +1,131 @@
+use std::time::Instant;
+
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::prelude::interaction::application_command::CommandDataOption;
+use serenity::model::Timestamp;
+
+use crate::ranking::{key, listed, ranked};
+use crate::storage::{Storage, StorageError};
+use crate::tr;
+use crate::util::{self, DAY};
+
+const TOP_ENTRIES: usize = 5;
+// Breakdowns only look this far back, so /stats never has to load the whole history.
+const WINDOW_DAYS: i64 = 30;
+
+fn inline(counts: &[(String, usize)], locale: &str) -> String {
+    if counts.is_empty() {
+        return tr!(locale, "nothing-shared");
+    }
+
+    counts
+        .iter()
+        .map(|(key, count)| format!("{key}: {count}"))
+        .collect::<Vec<_>>()
+        .join(" • ")
+}
+
+pub async fn run(
+    _options: &[CommandDataOption],
+    storage: &Storage,
+    guild_id: Option<u64>,
+    started: Instant,
+    locale: &str,
+) -> Result<String, StorageError> {
+    let now = Timestamp::now().unix_timestamp();
+
+    let total = storage.count_conversions(None, 0).await?;
+    let totals = match guild_id {
+        Some(_) => tr!(
+            locale,
+            "stats-total-guild",
+            total = total,
+            guild = storage.count_conversions(guild_id, 0).await?
+        ),
+        None => tr!(locale, "stats-total", total = total),
+    };
+
+    // Outside of a guild, the "local" numbers are simply everything.
+    let recent = storage
+        .conversions(guild_id, now - WINDOW_DAYS * DAY)
+        .await?;
+    let since = |days: i64| {
+        recent
+            .iter()
+            .filter(|record| record.timestamp >= now - days * DAY)
+            .count()
+    };
+    let top = |ranked: Vec<(String, usize)>| {
+        listed(&ranked, TOP_ENTRIES).unwrap_or_else(|| tr!(locale, "nothing-shared"))
+    };
+
+    let sections = [
+        (
+            tr!(locale, "stats-by-type"),
+            inline(
+                &ranked(recent.iter().map(|record| {
+                    (
+                        record.media_type.code().to_string(),
+                        tr!(locale, record.media_type.id()),
+                    )
+                })),
+                locale,
+            ),
+        ),
+        (
+            tr!(locale, "stats-by-platform"),
+            inline(
+                &ranked(recent.iter().map(|record| {
+                    let platform = record.platform.to_string();
+                    (platform.clone(), platform)
+                })),
+                locale,
+            ),
+        ),
+        (
+            tr!(locale, "stats-top-artists"),
+            top(ranked(recent.iter().filter_map(|record| {
+                let artist = record.artist.as_ref()?;
+                Some((key(&record.artist_id, artist), artist.clone()))
+            }))),
+        ),
+        (
+            tr!(locale, "stats-top-albums"),
+            top(ranked(recent.iter().filter_map(|record| {
+                let album = record.album.as_ref()?;
+                Some((key(&record.album_id, album), album.clone()))
+            }))),
+        ),
+    ];
+
+    let mut reply = format!(
+        "**{}**\n{totals}\n{}",
+        tr!(locale, "stats-title"),
+        tr!(
+            locale,
+            "stats-recent",
+            day = since(1),
+            week = since(7),
+            month = recent.len()
+        ),
+    );
+    for (title, body) in sections {
+        reply.push_str(&format!("\n\n**{title}**\n{body}"));
+    }
+    reply.push_str(&format!(
+        "\n\n{}",
+        tr!(
+            locale,
+            "stats-uptime",
+            uptime = util::human_duration(&started.elapsed(), locale)
+        )
+    ));
+
+    Ok(reply)
+}
+
+pub fn register(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    command
+        .name("stats")
+        .description("Show conversion statistics")
+}
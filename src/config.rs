@@ -0,0 +1,136 @@
+use std::{collections::HashMap, time::Duration};
+
+use log::*;
+use serenity::model::gateway::Activity;
+use serenity::model::user::OnlineStatus;
+
+const DEFAULT_PRESENCE: &str = "listening:Cider | {conversions} songs converted";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityKind {
+    Playing,
+    Listening,
+    Watching,
+    Competing,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PresenceTemplate {
+    pub kind: ActivityKind,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct PresenceConfig {
+    pub templates: Vec<PresenceTemplate>,
+    pub status: OnlineStatus,
+    pub interval: Duration,
+}
+
+/// Runtime configuration read from the environment (or `.env`), this is what `/admin reload`
+/// refreshes. Everything else, like `CIDER_LINK_BASE`, is only read on startup.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub presence: PresenceConfig,
+    /// Users allowed to run `/admin` on top of the application owner, from `ADMIN_IDS`.
+    pub admins: Vec<u64>,
+}
+
+impl ActivityKind {
+    pub fn activity(&self, text: &str) -> Activity {
+        match self {
+            ActivityKind::Playing => Activity::playing(text),
+            ActivityKind::Listening => Activity::listening(text),
+            ActivityKind::Watching => Activity::watching(text),
+            ActivityKind::Competing => Activity::competing(text),
+        }
+    }
+}
+
+impl PresenceTemplate {
+    /// Parses `kind:text`, templates without a known kind are treated as listening.
+    fn parse(template: &str) -> PresenceTemplate {
+        let (kind, text) = match template.split_once(':') {
+            Some(("playing", text)) => (ActivityKind::Playing, text),
+            Some(("listening", text)) => (ActivityKind::Listening, text),
+            Some(("watching", text)) => (ActivityKind::Watching, text),
+            Some(("competing", text)) => (ActivityKind::Competing, text),
+            _ => (ActivityKind::Listening, template),
+        };
+
+        PresenceTemplate {
+            kind,
+            text: text.trim().to_string(),
+        }
+    }
+}
+
+impl PresenceConfig {
+    /// `PRESENCE_TEMPLATES` is a `;` separated list such as
+    /// `listening:{conversions} songs converted;watching:{guilds} servers`.
+    fn from_vars(var: &impl Fn(&str) -> Option<String>) -> PresenceConfig {
+        let templates = var("PRESENCE_TEMPLATES")
+            .unwrap_or(DEFAULT_PRESENCE.to_string())
+            .split(';')
+            .filter(|template| !template.trim().is_empty())
+            .map(PresenceTemplate::parse)
+            .collect::<Vec<_>>();
+
+        let status = match var("PRESENCE_STATUS").as_deref() {
+            Some("online") => OnlineStatus::Online,
+            Some("idle") => OnlineStatus::Idle,
+            Some("invisible") => OnlineStatus::Invisible,
+            Some("dnd") | None => OnlineStatus::DoNotDisturb,
+            Some(other) => {
+                warn!("Unknown PRESENCE_STATUS {other}, using dnd");
+                OnlineStatus::DoNotDisturb
+            }
+        };
+
+        let interval = var("PRESENCE_INTERVAL")
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(30);
+
+        PresenceConfig {
+            templates: match templates.is_empty() {
+                true => vec![PresenceTemplate::parse(DEFAULT_PRESENCE)],
+                false => templates,
+            },
+            status,
+            // Discord rate limits presence updates, so don't let anyone go below 10 seconds.
+            interval: Duration::from_secs(interval.max(10)),
+        }
+    }
+}
+
+impl Config {
+    pub fn from_env() -> Config {
+        Config::from_vars(&|key| std::env::var(key).ok())
+    }
+
+    fn from_vars(var: &impl Fn(&str) -> Option<String>) -> Config {
+        Config {
+            presence: PresenceConfig::from_vars(var),
+            admins: var("ADMIN_IDS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|id| id.trim().parse().ok())
+                .collect(),
+        }
+    }
+
+    /// Builds a fresh config from `.env` on top of the environment we started with. The process
+    /// environment itself is left alone, changing it while other threads read it isn't sound.
+    pub fn reload() -> Config {
+        #[allow(deprecated)]
+        let file = match dotenv::dotenv_iter() {
+            Ok(vars) => vars.flatten().collect::<HashMap<_, _>>(),
+            Err(e) => {
+                info!("Not reloading .env: {e}");
+                HashMap::new()
+            }
+        };
+
+        Config::from_vars(&|key| file.get(key).cloned().or_else(|| std::env::var(key).ok()))
+    }
+}
@@ -1,22 +1,194 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, fmt, time::Duration};
 
 use log::*;
 use reqwest::{Method, Url};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serenity::builder::{CreateButton, CreateComponents, CreateEmbed};
+use serenity::model::application::component::ButtonStyle;
+use serenity::model::Timestamp;
+use serenity::utils::Colour;
 
-use crate::{api::AppleMusicApi, util, vpath::ValuePath};
+use crate::{
+    api::AppleMusicApi,
+    cider_links::{self, Action, CatalogRef, LinkButtons, LinkScheme},
+    commands,
+    guild::GuildConfig,
+    similar, tr, util,
+    vpath::ValuePath,
+};
 
 #[derive(Debug, Default)]
 pub struct EmbedInformation {
     pub title: String,
     pub description: String,
     pub footer: String,
+    /// Release date as timestamp markup, footers don't render it so it goes in a field.
+    pub released: Option<String>,
+    /// Extra details shown as embed fields, guilds can hide them with `/config fields`.
+    pub fields: Vec<(EmbedField, String)>,
+    /// Songs with lyrics get a button to show them.
+    pub has_lyrics: bool,
     pub artwork: String,
+    /// Taken from the artwork's background colour, `None` when apple doesn't have one.
+    pub colour: Option<Colour>,
     pub url: String,
+    pub storefront: String,
+    pub media_type: MediaType,
+    pub catalog_id: String,
+    pub artist: Option<String>,
+    pub artist_id: Option<String>,
+    pub album: Option<String>,
+    pub album_id: Option<String>,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
-enum MediaType {
+impl EmbedInformation {
+    pub fn catalog(&self) -> CatalogRef {
+        CatalogRef::new(&self.storefront, self.media_type, &self.catalog_id)
+    }
+
+    /// Fills in the conversion embed, leaving out the fields a guild turned off.
+    pub fn embed<'a>(
+        &self,
+        e: &'a mut CreateEmbed,
+        locale: &str,
+        config: &GuildConfig,
+    ) -> &'a mut CreateEmbed {
+        e.title(&self.title)
+            .url(&self.url)
+            .description(&self.description)
+            .footer(|f| f.text(&self.footer))
+            .timestamp(Timestamp::now());
+
+        // Not every page has artwork, discord rejects an empty thumbnail.
+        if !self.artwork.is_empty() {
+            e.thumbnail(&self.artwork);
+        }
+
+        if let Some(colour) = self.colour {
+            e.colour(colour);
+        }
+
+        if let Some(released) = &self.released {
+            e.field(tr!(locale, "released"), released, true);
+        }
+
+        for (field, value) in &self.fields {
+            if !config.hidden_fields.contains(field) {
+                e.field(tr!(locale, field.id()), value, field.inline());
+            }
+        }
+
+        // Discord only takes http links on buttons and in embeds, so app links are there to copy.
+        let links = &config.cider_links;
+        if links.scheme == LinkScheme::Cider {
+            let deep = [(links.play, Action::Play), (links.view, Action::View)]
+                .into_iter()
+                .filter(|(shown, _)| *shown)
+                .map(|(_, action)| format!("`{}`", self.catalog().deep(action)))
+                .collect::<Vec<_>>();
+
+            if !deep.is_empty() {
+                e.field(tr!(locale, "open-in-cider"), deep.join("\n"), false);
+            }
+        }
+        e
+    }
+
+    /// The Cider buttons the guild wants, the lyrics button for songs that have them, and the
+    /// similar button. Adds nothing if that leaves no buttons.
+    pub fn buttons<'a>(
+        &self,
+        c: &'a mut CreateComponents,
+        locale: &str,
+        links: &LinkButtons,
+    ) -> &'a mut CreateComponents {
+        let button = |label: &str, style: ButtonStyle| {
+            let mut button = CreateButton::default();
+            button.label(tr!(locale, label)).style(style);
+            button
+        };
+        let mut buttons = Vec::new();
+
+        if links.play {
+            let mut play = button("play-in-cider", ButtonStyle::Link);
+            play.url(self.catalog().web(Action::Play));
+            buttons.push(play);
+        }
+
+        if links.view {
+            let mut view = button("view-in-cider", ButtonStyle::Link);
+            view.url(self.catalog().web(Action::View));
+            buttons.push(view);
+        }
+
+        if self.has_lyrics {
+            let mut lyrics = button("lyrics-button", ButtonStyle::Secondary);
+            lyrics.custom_id(commands::lyrics::button_id(
+                &self.storefront,
+                &self.catalog_id,
+            ));
+            buttons.push(lyrics);
+        }
+
+        if let Some(similar_id) =
+            similar::button_id(self.media_type, &self.storefront, &self.catalog_id)
+        {
+            let mut similar = button("similar-button", ButtonStyle::Secondary);
+            similar.custom_id(similar_id);
+            buttons.push(similar);
+        }
+
+        if !buttons.is_empty() {
+            c.create_action_row(|r| {
+                for button in buttons {
+                    r.add_button(button);
+                }
+                r
+            });
+        }
+        c
+    }
+}
+
+/// Where the shared link originally pointed to before we converted it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Platform {
+    AppleMusic,
+    Spotify,
+}
+
+/// A single conversion, kept around so we can build statistics from it later.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionRecord {
+    pub timestamp: i64,
+    pub guild_id: Option<u64>,
+    #[serde(default)]
+    pub author_id: u64,
+    #[serde(default)]
+    pub author_name: String,
+    #[serde(default)]
+    pub channel_id: u64,
+    #[serde(default)]
+    pub message_id: u64,
+    pub platform: Platform,
+    pub media_type: MediaType,
+    #[serde(default)]
+    pub catalog_id: Option<String>,
+    pub title: String,
+    pub artist: Option<String>,
+    #[serde(default)]
+    pub artist_id: Option<String>,
+    pub album: Option<String>,
+    #[serde(default)]
+    pub album_id: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum MediaType {
     #[default]
     Song,
     Album,
@@ -24,10 +196,312 @@ enum MediaType {
     MusicVideo,
     Station,
     Artist,
+    Curator,
+    AppleCurator,
+    RadioShow,
+    RecordLabel,
+    Editorial,
+    SocialProfile,
+}
+
+/// Catalog details we can show on song and artist embeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbedField {
+    Genre,
+    Composer,
+    Track,
+    Rating,
+    Audio,
+    Label,
+    Copyright,
+    TopSongs,
+    LatestRelease,
+    Playlists,
+    Releases,
+    Featured,
+}
+
+impl EmbedField {
+    pub const ALL: [EmbedField; 12] = [
+        EmbedField::Genre,
+        EmbedField::Composer,
+        EmbedField::Track,
+        EmbedField::Rating,
+        EmbedField::Audio,
+        EmbedField::Label,
+        EmbedField::Copyright,
+        EmbedField::TopSongs,
+        EmbedField::LatestRelease,
+        EmbedField::Playlists,
+        EmbedField::Releases,
+        EmbedField::Featured,
+    ];
+
+    /// Catalog message for the field's name, also what `/config fields` calls it.
+    pub fn id(&self) -> &'static str {
+        match self {
+            EmbedField::Genre => "field-genre",
+            EmbedField::Composer => "field-composer",
+            EmbedField::Track => "field-track",
+            EmbedField::Rating => "field-rating",
+            EmbedField::Audio => "field-audio",
+            EmbedField::Label => "field-label",
+            EmbedField::Copyright => "field-copyright",
+            EmbedField::TopSongs => "field-top-songs",
+            EmbedField::LatestRelease => "field-latest-release",
+            EmbedField::Playlists => "field-playlists",
+            EmbedField::Releases => "field-releases",
+            EmbedField::Featured => "field-featured",
+        }
+    }
+
+    /// Short fields fit next to each other, copyright notices and lists don't.
+    pub fn inline(&self) -> bool {
+        !matches!(
+            self,
+            EmbedField::Copyright
+                | EmbedField::TopSongs
+                | EmbedField::Playlists
+                | EmbedField::Releases
+                | EmbedField::Featured
+        )
+    }
+}
+
+/// The resource's name, linked to Cider the way the guild's buttons are. Just the name if the
+/// guild turned the buttons off.
+fn linked(resource: &Value, links: &LinkButtons) -> Option<String> {
+    let name = resource.get_value_by_path("attributes.name")?;
+    let url = resource.get_value_by_path("attributes.url")?;
+    let name = name.as_str()?;
+    let url = url.as_str()?;
+
+    Some(match links.text_action() {
+        Some(action) => util::masked_link(name, &cider_links::web(url, action)),
+        None => name.to_string(),
+    })
+}
+
+/// The first five resources under `path`, numbered and linked to Cider.
+fn linked_list(resource: &Value, path: &str, links: &LinkButtons) -> Option<String> {
+    Some(
+        resource
+            .get_value_by_path(path)?
+            .as_array()?
+            .iter()
+            .filter_map(|item| linked(item, links))
+            .take(5)
+            .enumerate()
+            .map(|(i, item)| format!("{}. {item}", i + 1))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+    .filter(|list| !list.is_empty())
+}
+
+/// The resource's genres, `None` if there are none worth showing.
+fn genres(resource: &Value) -> Option<String> {
+    // "Music" is on almost everything, it doesn't tell anyone anything.
+    Some(
+        resource
+            .get_value_by_path("attributes.genreNames")?
+            .as_array()?
+            .iter()
+            .filter_map(Value::as_str)
+            .filter(|genre| *genre != "Music")
+            .collect::<Vec<_>>()
+            .join(", "),
+    )
+    .filter(|genres| !genres.is_empty())
+}
+
+/// Top songs, latest release and genres of an artist, every entry links to Cider.
+fn artist_fields(artist: &Value, links: &LinkButtons) -> Vec<(EmbedField, String)> {
+    let mut fields = Vec::new();
+
+    if let Some(top_songs) = linked_list(artist, "views.top-songs.data", links) {
+        fields.push((EmbedField::TopSongs, top_songs));
+    }
+
+    if let Some(latest) = artist.get_value_by_path("views.latest-release.data.0") {
+        if let Some(release) = linked(&latest, links) {
+            let released = latest
+                .get_value_by_path("attributes.releaseDate")
+                .and_then(|date| date.as_str().map(util::discord_date));
+
+            fields.push((
+                EmbedField::LatestRelease,
+                match released {
+                    Some(released) => format!("{release}\n{released}"),
+                    None => release,
+                },
+            ));
+        }
+    }
+
+    if let Some(genres) = genres(artist) {
+        fields.push((EmbedField::Genre, genres));
+    }
+
+    fields
+}
+
+/// The artwork's background colour, which apple sends as a hex string like "f4e1d3".
+fn accent_colour(resp: &Value) -> Option<Colour> {
+    let hex = resp.get_value_by_path("data.0.attributes.artwork.bgColor")?;
+    u32::from_str_radix(hex.as_str()?, 16).ok().map(Colour::new)
+}
+
+/// Id of the first related resource of `kind`, which apple only sends when it was included.
+fn related_id(resp: &Value, kind: &str) -> Option<String> {
+    resp.get_value_by_path(&format!("data.0.relationships.{kind}.data.0.id"))?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Builds the extra song fields from the catalog attributes, skipping whatever apple left out.
+fn song_fields(song: &Value, locale: &str) -> Vec<(EmbedField, String)> {
+    let text = |path: &str| {
+        song.get_value_by_path(path)
+            .and_then(|value| value.as_str().map(str::to_string))
+            .filter(|text| !text.is_empty())
+    };
+
+    let mut fields = Vec::new();
+
+    if let Some(genres) = genres(song) {
+        fields.push((EmbedField::Genre, genres));
+    }
+
+    if let Some(composer) = text("attributes.composerName") {
+        fields.push((EmbedField::Composer, composer));
+    }
+
+    let number = |path: &str| song.get_value_by_path(path)?.as_u64();
+    if let (Some(track), Some(disc)) = (
+        number("attributes.trackNumber"),
+        number("attributes.discNumber"),
+    ) {
+        fields.push((
+            EmbedField::Track,
+            tr!(locale, "track-number", track = track, disc = disc),
+        ));
+    }
+
+    match text("attributes.contentRating").as_deref() {
+        Some("explicit") => fields.push((EmbedField::Rating, tr!(locale, "rating-explicit"))),
+        Some("clean") => fields.push((EmbedField::Rating, tr!(locale, "rating-clean"))),
+        _ => {}
+    }
+
+    let badges = song
+        .get_value_by_path("attributes.audioTraits")
+        .and_then(|traits| {
+            Some(
+                traits
+                    .as_array()?
+                    .iter()
+                    .filter_map(|audio_trait| match audio_trait.as_str()? {
+                        "lossless" => Some("Lossless"),
+                        "hi-res-lossless" => Some("Hi-Res Lossless"),
+                        "atmos" => Some("Dolby Atmos"),
+                        "spatial" => Some("Spatial Audio"),
+                        _ => None,
+                    })
+                    .map(|badge| format!("`{badge}`"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            )
+        })
+        .filter(|badges| !badges.is_empty());
+
+    if let Some(badges) = badges {
+        fields.push((EmbedField::Audio, badges));
+    }
+
+    // Label and copyright only live on the album, which we ask for with include=albums. The
+    // artist, which `song_information` takes the artist id from, comes with include=artists.
+    if let Some(label) = text("relationships.albums.data.0.attributes.recordLabel") {
+        fields.push((EmbedField::Label, label));
+    }
+
+    if let Some(copyright) = text("relationships.albums.data.0.attributes.copyright") {
+        fields.push((EmbedField::Copyright, copyright));
+    }
+
+    fields
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Platform::AppleMusic => "Apple Music",
+            Platform::Spotify => "Spotify",
+        })
+    }
 }
 
 impl MediaType {
-    fn determine(url: &Url, query: &HashMap<String, String>) -> Option<MediaType> {
+    pub const ALL: [MediaType; 12] = [
+        MediaType::Song,
+        MediaType::Album,
+        MediaType::Playlist,
+        MediaType::MusicVideo,
+        MediaType::Station,
+        MediaType::Artist,
+        MediaType::Curator,
+        MediaType::AppleCurator,
+        MediaType::RadioShow,
+        MediaType::RecordLabel,
+        MediaType::Editorial,
+        MediaType::SocialProfile,
+    ];
+
+    /// Short and stable name for custom ids, which only fit 100 characters. Never change these,
+    /// buttons on old messages still carry them.
+    pub fn code(&self) -> &'static str {
+        match self {
+            MediaType::Song => "so",
+            MediaType::Album => "al",
+            MediaType::Playlist => "pl",
+            MediaType::MusicVideo => "mv",
+            MediaType::Station => "st",
+            MediaType::Artist => "ar",
+            MediaType::Curator => "cu",
+            MediaType::AppleCurator => "ac",
+            MediaType::RadioShow => "rs",
+            MediaType::RecordLabel => "rl",
+            MediaType::Editorial => "ed",
+            MediaType::SocialProfile => "sp",
+        }
+    }
+
+    /// Catalog message with the name of the kind, e.g. "Music video".
+    pub fn id(&self) -> &'static str {
+        match self {
+            MediaType::Song => "media-song",
+            MediaType::Album => "media-album",
+            MediaType::Playlist => "media-playlist",
+            MediaType::MusicVideo => "media-music-video",
+            MediaType::Station => "media-station",
+            MediaType::Artist => "media-artist",
+            MediaType::Curator => "media-curator",
+            MediaType::AppleCurator => "media-apple-curator",
+            MediaType::RadioShow => "media-radio-show",
+            MediaType::RecordLabel => "media-record-label",
+            MediaType::Editorial => "media-editorial",
+            MediaType::SocialProfile => "media-social-profile",
+        }
+    }
+
+    pub fn from_code(code: &str) -> Option<MediaType> {
+        MediaType::ALL
+            .into_iter()
+            .find(|media_type| media_type.code() == code)
+    }
+
+    pub fn determine(url: &Url, query: &HashMap<String, String>) -> Option<MediaType> {
         let segments = url.path_segments()?.collect::<Vec<&str>>();
 
         let media_type = segments.get(1)?;
@@ -44,6 +518,12 @@ impl MediaType {
                 "music-video" => Some(MediaType::MusicVideo),
                 "playlist" => Some(MediaType::Playlist),
                 "station" => Some(MediaType::Station),
+                "curator" => Some(MediaType::Curator),
+                "apple-curator" => Some(MediaType::AppleCurator),
+                "radio-show" | "show" => Some(MediaType::RadioShow),
+                "record-label" => Some(MediaType::RecordLabel),
+                "room" => Some(MediaType::Editorial),
+                "social-profile" => Some(MediaType::SocialProfile),
                 _ => {
                     warn!("Unknown media type {}", media_type);
                     info!("\turl: {}", &url);
@@ -52,6 +532,233 @@ impl MediaType {
             }
         }
     }
+
+    /// How apple music links name the kind, e.g. `music-video`.
+    pub fn segment(&self) -> &'static str {
+        match self {
+            MediaType::Song => "song",
+            MediaType::Album => "album",
+            MediaType::Playlist => "playlist",
+            MediaType::MusicVideo => "music-video",
+            MediaType::Station => "station",
+            MediaType::Artist => "artist",
+            MediaType::Curator => "curator",
+            MediaType::AppleCurator => "apple-curator",
+            MediaType::RadioShow => "radio-show",
+            MediaType::RecordLabel => "record-label",
+            MediaType::Editorial => "room",
+            MediaType::SocialProfile => "social-profile",
+        }
+    }
+
+    /// Where to find pages that are mostly a list of other things, and which field the list
+    /// goes in. `None` for everything with its own layout.
+    fn page(&self, storefront: &str, id: &str) -> Option<(String, EmbedField, &'static str)> {
+        Some(match self {
+            MediaType::Curator => (
+                format!("v1/catalog/{storefront}/curators/{id}?include=playlists"),
+                EmbedField::Playlists,
+                "relationships.playlists.data",
+            ),
+            // Radio shows are apple curators of the "Show" kind.
+            MediaType::AppleCurator | MediaType::RadioShow => (
+                format!("v1/catalog/{storefront}/apple-curators/{id}?include=playlists"),
+                EmbedField::Playlists,
+                "relationships.playlists.data",
+            ),
+            MediaType::RecordLabel => (
+                format!("v1/catalog/{storefront}/record-labels/{id}?views=latest-releases"),
+                EmbedField::Releases,
+                "views.latest-releases.data",
+            ),
+            MediaType::Editorial => (
+                format!("v1/editorial/{storefront}/rooms/{id}?include=contents"),
+                EmbedField::Featured,
+                "relationships.contents.data",
+            ),
+            MediaType::SocialProfile => (
+                format!("v1/social/{storefront}/social-profiles/{id}?include=public-playlists"),
+                EmbedField::Playlists,
+                "relationships.public-playlists.data",
+            ),
+            _ => return None,
+        })
+    }
+}
+
+/// The song request behind conversions. Alternates and recommendations ask for the exact same
+/// thing so they come out of the cache.
+pub fn song_endpoint(storefront: &str, id: &str) -> String {
+    format!("v1/catalog/{storefront}/songs/{id}?include=albums,artists&extend=audioTraits")
+}
+
+/// Looks a song up by its catalog id, also used to swap in another version of a shared song.
+pub async fn song_information(
+    api: &AppleMusicApi,
+    storefront: &str,
+    id: &str,
+    shared_by: &str,
+    locale: &str,
+) -> Option<EmbedInformation> {
+    let mut information = EmbedInformation {
+        storefront: storefront.to_string(),
+        media_type: MediaType::Song,
+        ..Default::default()
+    };
+
+    let Ok(resp) = api
+        .request_endpoint(Method::GET, &song_endpoint(storefront, id))
+        .await
+    else {
+        warn!("failed to request song {id} from the apple music api");
+        return None;
+    };
+
+    // return useless values instead of panicking
+    information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
+    information.fields = song_fields(&resp.get_value_by_path("data.0")?, locale);
+
+    information.has_lyrics = resp
+        .get_value_by_path("data.0.attributes.hasLyrics")
+        .and_then(|value| value.as_bool())
+        .unwrap_or(false);
+
+    information.album_id = related_id(&resp, "albums");
+
+    information.artist_id = related_id(&resp, "artists");
+
+    information.title = resp
+        .get_value_by_path("data.0.attributes.name")?
+        .as_str()?
+        .to_string();
+
+    information.url = resp
+        .get_value_by_path("data.0.attributes.url")?
+        .as_str()?
+        .to_string();
+
+    information.album = Some(
+        resp.get_value_by_path("data.0.attributes.albumName")?
+            .as_str()?
+            .to_string(),
+    );
+
+    information.artist = Some(
+        resp.get_value_by_path("data.0.attributes.artistName")?
+            .as_str()?
+            .to_string(),
+    );
+
+    information.description = tr!(
+        locale,
+        "listen-by",
+        title = information.album.as_deref()?,
+        artist = information.artist.as_deref()?
+    );
+
+    information.colour = accent_colour(&resp);
+
+    information.artwork = util::wh(
+        resp.get_value_by_path("data.0.attributes.artwork.url")?
+            .as_str()?,
+        512,
+        512,
+    );
+
+    information.released = resp
+        .get_value_by_path("data.0.attributes.releaseDate")
+        .and_then(|date| date.as_str().map(util::discord_date));
+
+    information.footer = format!(
+        "{} | {}",
+        tr!(locale, "shared-by", name = shared_by),
+        util::milli_to_hhmmss(&Duration::from_millis(
+            resp.get_value_by_path("data.0.attributes.durationInMillis")?
+                .as_u64()
+                .unwrap_or(0),
+        )),
+    );
+
+    #[cfg(feature = "dominant-colour")]
+    if information.colour.is_none() {
+        information.colour =
+            util::dominant_colour(&*api.client.read().await, &information.artwork).await;
+    }
+
+    Some(information)
+}
+
+fn video_endpoint(storefront: &str, id: &str) -> String {
+    format!("v1/catalog/{storefront}/music-videos/{id}?include=artists")
+}
+
+fn video_information(
+    resp: &Value,
+    storefront: &str,
+    shared_by: &str,
+    locale: &str,
+) -> Option<EmbedInformation> {
+    let mut information = EmbedInformation {
+        storefront: storefront.to_string(),
+        media_type: MediaType::MusicVideo,
+        ..Default::default()
+    };
+
+    information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
+    information.artist_id = related_id(resp, "artists");
+
+    information.title = resp
+        .get_value_by_path("data.0.attributes.name")?
+        .as_str()?
+        .to_string();
+
+    information.url = resp
+        .get_value_by_path("data.0.attributes.url")?
+        .as_str()?
+        .to_string();
+
+    information.artist = Some(
+        resp.get_value_by_path("data.0.attributes.artistName")?
+            .as_str()?
+            .to_string(),
+    );
+
+    information.description = tr!(
+        locale,
+        "listen-by",
+        title = &information.title,
+        artist = information.artist.as_deref()?
+    );
+
+    information.colour = accent_colour(resp);
+
+    information.artwork = util::wh(
+        resp.get_value_by_path("data.0.attributes.artwork.url")?
+            .as_str()?,
+        512,
+        512,
+    );
+
+    information.released = resp
+        .get_value_by_path("data.0.attributes.releaseDate")
+        .and_then(|date| date.as_str().map(util::discord_date));
+
+    information.footer = format!(
+        "{} | {}",
+        tr!(locale, "shared-by", name = shared_by),
+        util::human_duration(
+            &Duration::from_millis(
+                resp.get_value_by_path("data.0.attributes.durationInMillis")?
+                    .as_u64()
+                    .unwrap_or(0),
+            ),
+            locale
+        ),
+    );
+
+    Some(information)
 }
 
 pub async fn get_information(
@@ -60,72 +767,31 @@ pub async fn get_information(
     storefront: &str,
     query: &HashMap<String, String>,
     message: &serenity::model::prelude::Message,
+    locale: &str,
+    links: &LinkButtons,
 ) -> Option<EmbedInformation> {
     // Create a place to store embed information for all of the follwing cases.
-    let mut information = EmbedInformation::default();
+    let mut information = EmbedInformation {
+        storefront: storefront.to_string(),
+        ..Default::default()
+    };
 
     // Determine what type of media it is.
     if let Some(media) = MediaType::determine(url, query) {
         info!("Converting media type {:?}", &media);
+        information.media_type = media;
+        tracing::Span::current().record("media_type", format!("{media:?}").as_str());
         match media {
             MediaType::Song => {
                 let id = match query.get("i") {
                     Some(i) => i,
-                    None => url.path_segments()?.last()?,
+                    None => url.path_segments()?.next_back()?,
                 };
 
-                let Ok(resp) = api
-                    .request_endpoint(
-                        Method::GET,
-                        &format!("v1/catalog/{}/songs/{}", storefront, &id),
-                    )
-                    .await
-                else {
-                    warn!("failed to request song {id} from the apple music api");
-                    return None;
-                };
-
-                // return useless values instead of panicking
-                information.title = resp
-                    .get_value_by_path("data.0.attributes.name")?
-                    .as_str()?
-                    .to_string();
-
-                information.url = resp
-                    .get_value_by_path("data.0.attributes.url")?
-                    .as_str()?
-                    .to_string();
-
-                information.description = format!(
-                    "Listen to {} by {} on Cider",
-                    resp.get_value_by_path("data.0.attributes.albumName")?
-                        .as_str()?,
-                    resp.get_value_by_path("data.0.attributes.artistName")?
-                        .as_str()?
-                );
-
-                information.artwork = util::wh(
-                    resp.get_value_by_path("data.0.attributes.artwork.url")
-                        .unwrap()
-                        .as_str()?,
-                    512,
-                    512,
-                );
-
-                information.footer = format!(
-                    "Shared by {} | {} • {}",
-                    message.author.name,
-                    util::milli_to_hhmmss(&Duration::from_millis(
-                        resp.get_value_by_path("data.0.attributes.durationInMillis")?
-                            .as_u64()
-                            .unwrap_or(0),
-                    )),
-                    resp.get_value_by_path("data.0.attributes.releaseDate")?
-                        .as_str()?
-                )
+                return song_information(api, storefront, id, &message.author.name, locale).await;
             }
             MediaType::Album => {
-                let id = url.path_segments()?.last()?;
+                let id = url.path_segments()?.next_back()?;
                 let Ok(resp) = api
                     .request_endpoint(
                         Method::GET,
@@ -149,6 +815,12 @@ pub async fn get_information(
                         .unwrap_or(0);
                 }
 
+                information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
+                information.album_id = Some(information.catalog_id.clone());
+
+                information.artist_id = related_id(&resp, "artists");
+
                 information.title = resp
                     .get_value_by_path("data.0.attributes.name")?
                     .as_str()?
@@ -159,13 +831,23 @@ pub async fn get_information(
                     .as_str()?
                     .to_string();
 
-                information.description = format!(
-                    "Listen to {} by {} on Cider",
-                    &information.title,
+                information.album = Some(information.title.clone());
+
+                information.artist = Some(
                     resp.get_value_by_path("data.0.attributes.artistName")?
                         .as_str()?
+                        .to_string(),
                 );
 
+                information.description = tr!(
+                    locale,
+                    "listen-by",
+                    title = &information.title,
+                    artist = information.artist.as_deref()?
+                );
+
+                information.colour = accent_colour(&resp);
+
                 information.artwork = util::wh(
                     resp.get_value_by_path("data.0.attributes.artwork.url")?
                         .as_str()?,
@@ -173,16 +855,18 @@ pub async fn get_information(
                     512,
                 );
 
+                information.released = resp
+                    .get_value_by_path("data.0.attributes.releaseDate")
+                    .and_then(|date| date.as_str().map(util::discord_date));
+
                 information.footer = format!(
-                    "Shared by {} | {} • {}",
-                    message.author.name,
-                    util::milli_to_hhmmss(&Duration::from_millis(total_duration)),
-                    resp.get_value_by_path("data.0.attributes.releaseDate")?
-                        .as_str()?
+                    "{} | {}",
+                    tr!(locale, "shared-by", name = &message.author.name),
+                    util::human_duration(&Duration::from_millis(total_duration), locale),
                 )
             }
             MediaType::Station => {
-                let id = url.path_segments()?.last()?;
+                let id = url.path_segments()?.next_back()?;
                 let Ok(resp) = api
                     .request_endpoint(
                         Method::GET,
@@ -194,6 +878,8 @@ pub async fn get_information(
                     return None;
                 };
 
+                information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
                 information.title = resp
                     .get_value_by_path("data.0.attributes.name")?
                     .as_str()?
@@ -204,7 +890,9 @@ pub async fn get_information(
                     .as_str()?
                     .to_string();
 
-                information.description = format!("Tune into {} on Cider", &information.title);
+                information.description = tr!(locale, "tune-into", title = &information.title);
+
+                information.colour = accent_colour(&resp);
 
                 information.artwork = util::wh(
                     resp.get_value_by_path("data.0.attributes.artwork.url")?
@@ -213,10 +901,10 @@ pub async fn get_information(
                     512,
                 );
 
-                information.footer = format!("Shared by {}", message.author.name)
+                information.footer = tr!(locale, "shared-by", name = &message.author.name)
             }
             MediaType::Playlist => {
-                let id = url.path_segments()?.last()?;
+                let id = url.path_segments()?.next_back()?;
                 let Ok(resp) = api
                     .request_endpoint(
                         Method::GET,
@@ -239,6 +927,8 @@ pub async fn get_information(
                         .unwrap_or(0);
                 }
 
+                information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
                 information.title = resp
                     .get_value_by_path("data.0.attributes.name")?
                     .as_str()?
@@ -249,14 +939,16 @@ pub async fn get_information(
                     .as_str()?
                     .to_string();
 
-                information.description = format!(
-                    "Listen to {} by {} on Cider",
-                    &information.title,
-                    resp.get_value_by_path("data.0.attributes.curatorName")?
-                        .as_str()
-                        .unwrap_or("N/A")
+                let curator = resp.get_value_by_path("data.0.attributes.curatorName")?;
+                information.description = tr!(
+                    locale,
+                    "listen-by",
+                    title = &information.title,
+                    artist = curator.as_str().unwrap_or("N/A")
                 );
 
+                information.colour = accent_colour(&resp);
+
                 information.artwork = util::wh(
                     resp.get_value_by_path("data.0.attributes.artwork.url")?
                         .as_str()?,
@@ -265,66 +957,32 @@ pub async fn get_information(
                 );
 
                 information.footer = format!(
-                    "Shared by {} | {}",
-                    message.author.name,
-                    util::milli_to_hhmmss(&Duration::from_millis(total_duration)),
+                    "{} | {}",
+                    tr!(locale, "shared-by", name = &message.author.name),
+                    util::human_duration(&Duration::from_millis(total_duration), locale),
                 )
             }
             MediaType::MusicVideo => {
-                let id = url.path_segments()?.last()?;
+                let id = url.path_segments()?.next_back()?;
                 let Ok(resp) = api
-                    .request_endpoint(
-                        Method::GET,
-                        &format!("v1/catalog/{}/music-video/{}", storefront, id),
-                    )
+                    .request_endpoint(Method::GET, &video_endpoint(storefront, id))
                     .await
                 else {
-                    warn!("failed to request album {id} from the apple music api");
+                    warn!("failed to request music video {id} from the apple music api");
                     return None;
                 };
 
-                information.title = resp
-                    .get_value_by_path("data.0.attributes.name")?
-                    .as_str()?
-                    .to_string();
-
-                information.url = resp
-                    .get_value_by_path("data.0.attributes.url")?
-                    .as_str()?
-                    .to_string();
-
-                information.description = format!(
-                    "Listen to {} by {} on Cider",
-                    &information.title,
-                    resp.get_value_by_path("data.0.attributes.artistName")?
-                        .as_str()?
-                );
-
-                information.artwork = util::wh(
-                    resp.get_value_by_path("data.0.attributes.artwork.url")?
-                        .as_str()?,
-                    512,
-                    512,
-                );
-
-                information.footer = format!(
-                    "Shared by {} | {} • {}",
-                    message.author.name,
-                    util::milli_to_hhmmss(&Duration::from_millis(
-                        resp.get_value_by_path("data.0.attributes.durationInMillis")?
-                            .as_u64()
-                            .unwrap_or(0),
-                    )),
-                    resp.get_value_by_path("data.0.attributes.releaseDate")?
-                        .as_str()?
-                )
+                information = video_information(&resp, storefront, &message.author.name, locale)?;
             }
             MediaType::Artist => {
-                let id = url.path_segments()?.last()?;
+                let id = url.path_segments()?.next_back()?;
                 let Ok(resp) = api
                     .request_endpoint(
                         Method::GET,
-                        &format!("v1/catalog/{}/artists/{}", storefront, id),
+                        &format!(
+                            "v1/catalog/{}/artists/{}?views=top-songs,latest-release&extend=artistBio",
+                            storefront, id
+                        ),
                     )
                     .await
                 else {
@@ -332,6 +990,10 @@ pub async fn get_information(
                     return None;
                 };
 
+                information.catalog_id = resp.get_value_by_path("data.0.id")?.as_str()?.to_string();
+
+                information.artist_id = Some(information.catalog_id.clone());
+
                 information.title = resp
                     .get_value_by_path("data.0.attributes.name")?
                     .as_str()?
@@ -342,7 +1004,23 @@ pub async fn get_information(
                     .as_str()?
                     .to_string();
 
-                information.description = format!("Listen to {} on Cider", &information.title);
+                information.artist = Some(information.title.clone());
+
+                // Use a bit of the bio when apple has one, it says more than our usual line.
+                let bio = resp
+                    .get_value_by_path("data.0.attributes.editorialNotes.short")
+                    .or_else(|| resp.get_value_by_path("data.0.attributes.artistBio"))
+                    .and_then(|bio| bio.as_str().map(|bio| util::snippet(bio, 300)))
+                    .filter(|bio| !bio.is_empty());
+
+                information.description = match bio {
+                    Some(bio) => bio,
+                    None => tr!(locale, "listen", title = &information.title),
+                };
+
+                information.fields = artist_fields(&resp.get_value_by_path("data.0")?, links);
+
+                information.colour = accent_colour(&resp);
 
                 information.artwork = util::wh(
                     resp.get_value_by_path("data.0.attributes.artwork.url")?
@@ -351,7 +1029,55 @@ pub async fn get_information(
                     512,
                 );
 
-                information.footer = format!("Shared by {}", message.author.name)
+                information.footer = tr!(locale, "shared-by", name = &message.author.name)
+            }
+            MediaType::Curator
+            | MediaType::AppleCurator
+            | MediaType::RadioShow
+            | MediaType::RecordLabel
+            | MediaType::Editorial
+            | MediaType::SocialProfile => {
+                let id = url.path_segments()?.next_back()?;
+                let (endpoint, field, list) = media.page(storefront, id)?;
+
+                let Ok(resp) = api.request_endpoint(Method::GET, &endpoint).await else {
+                    warn!("failed to request {media:?} {id} from the apple music api");
+                    return None;
+                };
+
+                let page = resp.get_value_by_path("data.0")?;
+                let text = |path: &str| {
+                    page.get_value_by_path(path)
+                        .and_then(|value| value.as_str().map(str::to_string))
+                        .filter(|text| !text.is_empty())
+                };
+
+                information.catalog_id = text("id")?;
+
+                // Editorial rooms call their name a title.
+                information.title = text("attributes.name").or_else(|| text("attributes.title"))?;
+
+                information.url = text("attributes.url").unwrap_or_else(|| url.to_string());
+
+                information.description = text("attributes.editorialNotes.short")
+                    .or_else(|| text("attributes.editorialNotes.standard"))
+                    .or_else(|| text("attributes.description.standard"))
+                    .or_else(|| text("attributes.description.short"))
+                    .map(|notes| util::snippet(&notes, 300))
+                    .or_else(|| text("attributes.handle").map(|handle| format!("@{handle}")))
+                    .unwrap_or_else(|| tr!(locale, "listen", title = &information.title));
+
+                if let Some(items) = linked_list(&page, list, links) {
+                    information.fields.push((field, items));
+                }
+
+                information.colour = accent_colour(&resp);
+
+                information.artwork = text("attributes.artwork.url")
+                    .map(|artwork| util::wh(&artwork, 512, 512))
+                    .unwrap_or_default();
+
+                information.footer = tr!(locale, "shared-by", name = &message.author.name)
             }
         }
     } else {
@@ -359,5 +1085,251 @@ pub async fn get_information(
         return None;
     }
 
+    #[cfg(feature = "dominant-colour")]
+    if information.colour.is_none() {
+        information.colour =
+            util::dominant_colour(&*api.client.read().await, &information.artwork).await;
+    }
+
     Some(information)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SONG: &str = include_str!("../fixtures/catalog/song.json");
+    const ARTIST: &str = include_str!("../fixtures/catalog/artist.json");
+    const MUSIC_VIDEO: &str = include_str!("../fixtures/catalog/music-video.json");
+
+    fn determine(link: &str) -> Option<MediaType> {
+        let url = Url::parse(link).unwrap();
+        let query = url
+            .query_pairs()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+
+        MediaType::determine(&url, &query)
+    }
+
+    #[test]
+    fn song_on_album() {
+        assert_eq!(
+            determine("https://music.apple.com/us/album/folklore/1528112358?i=1528112362"),
+            Some(MediaType::Song)
+        );
+    }
+
+    #[test]
+    fn catalog_kinds() {
+        assert_eq!(
+            determine("https://music.apple.com/us/song/cardigan/1528112362"),
+            Some(MediaType::Song)
+        );
+        assert_eq!(
+            determine("https://music.apple.com/us/album/folklore/1528112358"),
+            Some(MediaType::Album)
+        );
+        assert_eq!(
+            determine("https://music.apple.com/us/artist/taylor-swift/159260351"),
+            Some(MediaType::Artist)
+        );
+    }
+
+    #[test]
+    fn curator() {
+        assert_eq!(
+            determine("https://music.apple.com/us/curator/pitchfork/976439448"),
+            Some(MediaType::Curator)
+        );
+    }
+
+    #[test]
+    fn apple_curator() {
+        assert_eq!(
+            determine("https://music.apple.com/us/apple-curator/apple-music-pop/976439587"),
+            Some(MediaType::AppleCurator)
+        );
+    }
+
+    #[test]
+    fn radio_show() {
+        assert_eq!(
+            determine("https://music.apple.com/us/radio-show/the-zane-lowe-show/1461045817"),
+            Some(MediaType::RadioShow)
+        );
+        assert_eq!(
+            determine("https://music.apple.com/gb/show/rap-life-radio/1461045946"),
+            Some(MediaType::RadioShow)
+        );
+    }
+
+    #[test]
+    fn record_label() {
+        assert_eq!(
+            determine("https://music.apple.com/us/record-label/xl-recordings/1543411840"),
+            Some(MediaType::RecordLabel)
+        );
+    }
+
+    #[test]
+    fn editorial_room() {
+        assert_eq!(
+            determine("https://music.apple.com/us/room/1532200949"),
+            Some(MediaType::Editorial)
+        );
+    }
+
+    #[test]
+    fn social_profile() {
+        assert_eq!(
+            determine("https://music.apple.com/us/social-profile/cider/1234567890"),
+            Some(MediaType::SocialProfile)
+        );
+    }
+
+    #[test]
+    fn unknown_kind() {
+        assert_eq!(
+            determine("https://music.apple.com/us/browse/top-charts"),
+            None
+        );
+    }
+
+    #[test]
+    fn page_endpoints() {
+        let (endpoint, field, _) = MediaType::RadioShow.page("us", "1461045817").unwrap();
+        assert_eq!(
+            endpoint,
+            "v1/catalog/us/apple-curators/1461045817?include=playlists"
+        );
+        assert_eq!(field, EmbedField::Playlists);
+
+        assert!(MediaType::Song.page("us", "1").is_none());
+    }
+
+    #[test]
+    fn song_fixture() {
+        let resp: Value = serde_json::from_str(SONG).unwrap();
+        let song = resp.get_value_by_path("data.0").unwrap();
+
+        assert_eq!(
+            song_fields(&song, "en-US"),
+            vec![
+                (EmbedField::Genre, "Alternative, Pop".to_string()),
+                (
+                    EmbedField::Composer,
+                    "Taylor Swift & Aaron Dessner".to_string()
+                ),
+                (EmbedField::Track, "2 on disc 1".to_string()),
+                (EmbedField::Rating, "🅴 Explicit".to_string()),
+                (
+                    EmbedField::Audio,
+                    "`Dolby Atmos` `Lossless` `Spatial Audio`".to_string()
+                ),
+                (EmbedField::Label, "Taylor Swift".to_string()),
+                (EmbedField::Copyright, "℗ 2020 Taylor Swift".to_string()),
+            ]
+        );
+
+        assert_eq!(related_id(&resp, "albums").as_deref(), Some("1528112358"));
+        assert_eq!(related_id(&resp, "artists").as_deref(), Some("159260351"));
+    }
+
+    #[test]
+    fn artist_fixture() {
+        let resp: Value = serde_json::from_str(ARTIST).unwrap();
+        let play = |link: &str| cider_links::web(link, Action::Play);
+
+        assert_eq!(
+            artist_fields(
+                &resp.get_value_by_path("data.0").unwrap(),
+                &LinkButtons::default()
+            ),
+            vec![
+                (
+                    EmbedField::TopSongs,
+                    format!(
+                        "1. [cardigan]({})\n2. [All Too Well \\(10 Minute Version\\) \\[Taylor's Version\\] \\[From The Vault\\]]({})",
+                        play("https://music.apple.com/us/album/cardigan/1528112358?i=1528112362"),
+                        play("https://music.apple.com/us/album/all-too-well/1590368448?i=1590368459"),
+                    )
+                ),
+                (
+                    EmbedField::LatestRelease,
+                    format!(
+                        "[folklore]({})\n<t:1595548800:D>",
+                        play("https://music.apple.com/us/album/folklore/1528112358")
+                    )
+                ),
+                (EmbedField::Genre, "Pop".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn lists_follow_the_link_buttons() {
+        let resp: Value = serde_json::from_str(ARTIST).unwrap();
+        let artist = resp.get_value_by_path("data.0").unwrap();
+        let url = "https://music.apple.com/us/album/cardigan/1528112358?i=1528112362";
+
+        let view_only = LinkButtons {
+            play: false,
+            ..Default::default()
+        };
+        assert!(linked_list(&artist, "views.top-songs.data", &view_only)
+            .unwrap()
+            .starts_with(&format!(
+                "1. [cardigan]({})\n",
+                cider_links::web(url, Action::View)
+            )));
+
+        let none = LinkButtons {
+            play: false,
+            view: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            linked_list(&artist, "views.top-songs.data", &none).unwrap(),
+            "1. cardigan\n2. All Too Well (10 Minute Version) [Taylor's Version] [From The Vault]"
+        );
+    }
+
+    #[test]
+    fn song_fields_skip_what_is_missing() {
+        let song = serde_json::json!({
+            "attributes": {
+                "genreNames": ["Music"],
+                "trackNumber": 3,
+                "contentRating": "clean",
+                "audioTraits": ["lossy-stereo"]
+            }
+        });
+
+        assert_eq!(
+            song_fields(&song, "de"),
+            vec![(EmbedField::Rating, tr!("de", "rating-clean"))]
+        );
+    }
+
+    #[test]
+    fn video_fixture() {
+        assert_eq!(
+            video_endpoint("us", "1534493945"),
+            "v1/catalog/us/music-videos/1534493945?include=artists"
+        );
+
+        let resp: Value = serde_json::from_str(MUSIC_VIDEO).unwrap();
+        let video = video_information(&resp, "us", "someone", "en-US").unwrap();
+
+        assert_eq!(video.media_type, MediaType::MusicVideo);
+        assert_eq!(video.catalog_id, "1534493945");
+        assert_eq!(video.title, "cardigan");
+        assert_eq!(video.artist.as_deref(), Some("Taylor Swift"));
+        assert_eq!(video.artist_id.as_deref(), Some("159260351"));
+        assert_eq!(video.colour, Some(Colour::new(0x1b1c1e)));
+        assert!(video.artwork.ends_with("512x512mv.jpg"));
+        assert!(video.released.is_some());
+        assert!(video.footer.contains("someone"));
+    }
+}
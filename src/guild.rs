@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cider_links::LinkButtons;
+use crate::conversion::EmbedField;
+
+/// Per guild settings, changed through `/config` and kept in [`crate::storage::Storage`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GuildConfig {
+    /// Channel the weekly digest gets posted to, `None` disables it.
+    pub digest_channel: Option<u64>,
+    /// When we last posted (or scheduled) the digest, in unix seconds.
+    pub last_digest: i64,
+    /// Language for messages that aren't answers to someone, `None` follows the server's language.
+    pub locale: Option<String>,
+    /// Embed fields turned off with `/config fields`, everything shows by default.
+    pub hidden_fields: Vec<EmbedField>,
+    /// Artists whose new releases get announced, added with `/follow artist`.
+    pub follows: Vec<Follow>,
+    /// Which Cider buttons go on embeds, changed with `/config links`.
+    pub cider_links: LinkButtons,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Follow {
+    pub artist_id: String,
+    pub artist: String,
+    pub storefront: String,
+    /// Channel the announcements go to.
+    pub channel: u64,
+    /// The newest release we know about, kept here so a restart doesn't announce it again.
+    pub last_release: Option<String>,
+}
@@ -0,0 +1,141 @@
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use log::*;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+// The token updater refreshes every 30 minutes, so give it one missed cycle before we
+// stop reporting as ready.
+const TOKEN_MAX_AGE: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Upstream {
+    AppleMusic,
+    SongLink,
+    CiderApi,
+}
+
+/// Shared view of everything the orchestrator cares about, updated from the
+/// event handler, the token updater and the upstream clients.
+#[derive(Debug, Default)]
+pub struct Health {
+    shards: RwLock<HashMap<u64, bool>>,
+    token_refreshed: RwLock<Option<SystemTime>>,
+    upstreams: RwLock<HashMap<Upstream, SystemTime>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Report {
+    pub ready: bool,
+    pub shards: HashMap<u64, bool>,
+    pub token_age_secs: Option<u64>,
+    pub upstreams: HashMap<Upstream, u64>,
+}
+
+impl Health {
+    pub async fn set_shard(&self, shard: u64, connected: bool) {
+        self.shards.write().await.insert(shard, connected);
+    }
+
+    pub async fn token_refreshed(&self) {
+        *self.token_refreshed.write().await = Some(SystemTime::now());
+    }
+
+    pub async fn upstream_ok(&self, upstream: Upstream) {
+        self.upstreams
+            .write()
+            .await
+            .insert(upstream, SystemTime::now());
+    }
+
+    pub async fn report(&self) -> Report {
+        let shards = self.shards.read().await.clone();
+
+        let token_age = self
+            .token_refreshed
+            .read()
+            .await
+            .and_then(|at| at.elapsed().ok());
+
+        let upstreams = self
+            .upstreams
+            .read()
+            .await
+            .iter()
+            .map(|(upstream, at)| (*upstream, unix_secs(at)))
+            .collect();
+
+        let shards_up = !shards.is_empty() && shards.values().all(|connected| *connected);
+        let token_fresh = token_age.is_some_and(|age| age < TOKEN_MAX_AGE);
+
+        Report {
+            ready: shards_up && token_fresh,
+            shards,
+            token_age_secs: token_age.map(|age| age.as_secs()),
+            upstreams,
+        }
+    }
+}
+
+fn unix_secs(time: &SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+async fn route(health: Arc<Health>, req: Request<Body>) -> Result<Response<Body>, Infallible> {
+    if req.method() != Method::GET {
+        return Ok(respond(StatusCode::METHOD_NOT_ALLOWED, "{}".to_string()));
+    }
+
+    let report = health.report().await;
+    let body = serde_json::to_string(&report).unwrap_or_else(|_| "{}".to_string());
+
+    Ok(match req.uri().path() {
+        // Liveness only says the process is up and answering, restarts are handled by readiness.
+        "/healthz" => respond(StatusCode::OK, body),
+        "/readyz" if report.ready => respond(StatusCode::OK, body),
+        "/readyz" => respond(StatusCode::SERVICE_UNAVAILABLE, body),
+        _ => respond(StatusCode::NOT_FOUND, "{}".to_string()),
+    })
+}
+
+fn respond(status: StatusCode, body: String) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
+pub async fn serve(health: Arc<Health>, addr: SocketAddr) {
+    let make_service = make_service_fn(move |_| {
+        let health = health.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |req| route(health.clone(), req))) }
+    });
+
+    let builder = match Server::try_bind(&addr) {
+        Ok(builder) => builder,
+        Err(why) => {
+            error!("Unable to bind health endpoint to {addr}: {why}");
+            return;
+        }
+    };
+
+    info!("Health endpoint listening on {addr}");
+
+    if let Err(why) = builder.serve(make_service).await {
+        error!("Health endpoint stopped: {why}");
+    }
+}
@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use fluent_bundle::{concurrent::FluentBundle, FluentArgs, FluentResource};
+use log::*;
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value};
+use serenity::builder::CreateApplicationCommand;
+use serenity::model::id::GuildId;
+use serenity::prelude::Context;
+
+use crate::guild::GuildConfig;
+
+pub const DEFAULT_LOCALE: &str = "en-US";
+
+/// Every catalog we ship, keyed by the discord locale it is for.
+pub const LOCALES: &[(&str, &str, &str)] = &[
+    ("en-US", "English", include_str!("../locales/en-US.ftl")),
+    ("de", "Deutsch", include_str!("../locales/de.ftl")),
+    ("es-ES", "Español", include_str!("../locales/es-ES.ftl")),
+];
+
+static BUNDLES: Lazy<HashMap<&'static str, FluentBundle<FluentResource>>> = Lazy::new(|| {
+    LOCALES
+        .iter()
+        .map(|(locale, _, source)| {
+            let resource =
+                FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, errors)| {
+                    warn!("Catalog {locale} has errors: {errors:?}");
+                    resource
+                });
+
+            let mut bundle = FluentBundle::new_concurrent(vec![locale.parse().unwrap()]);
+            // Discord renders the isolation marks around arguments as garbage in some places.
+            bundle.set_use_isolating(false);
+            if let Err(errors) = bundle.add_resource(resource) {
+                warn!("Catalog {locale} has duplicate messages: {errors:?}");
+            }
+
+            (*locale, bundle)
+        })
+        .collect()
+});
+
+/// Picks the catalog for a discord locale, falling back to the same language in another
+/// region (es-419 gets es-ES) and then to English.
+pub fn resolve(locale: &str) -> &'static str {
+    let language = locale.split('-').next().unwrap_or(locale);
+
+    LOCALES
+        .iter()
+        .map(|(known, ..)| *known)
+        .find(|known| *known == locale)
+        .or_else(|| {
+            LOCALES
+                .iter()
+                .map(|(known, ..)| *known)
+                .find(|known| known.split('-').next() == Some(language))
+        })
+        .unwrap_or(DEFAULT_LOCALE)
+}
+
+/// The name we show for a locale, e.g. "Deutsch".
+pub fn language(locale: &str) -> &'static str {
+    let locale = resolve(locale);
+    LOCALES
+        .iter()
+        .find(|(known, ..)| *known == locale)
+        .map(|(_, name, _)| *name)
+        .unwrap_or("English")
+}
+
+/// Language for messages sent to a whole guild rather than answering someone: whatever the
+/// guild picked with `/config language`, otherwise the guild's preferred locale.
+pub fn guild_locale(ctx: &Context, guild_id: u64, config: &GuildConfig) -> String {
+    config
+        .locale
+        .clone()
+        .or_else(|| {
+            ctx.cache
+                .guild_field(GuildId(guild_id), |guild| guild.preferred_locale.clone())
+        })
+        .unwrap_or_else(|| DEFAULT_LOCALE.to_string())
+}
+
+fn format(locale: &str, id: &str, args: Option<&FluentArgs>) -> Option<String> {
+    let bundle = BUNDLES.get(locale)?;
+    let pattern = bundle.get_message(id)?.value()?;
+
+    let mut errors = Vec::new();
+    let text = bundle.format_pattern(pattern, args, &mut errors);
+    if !errors.is_empty() {
+        warn!("Unable to format {id} for {locale}: {errors:?}");
+    }
+
+    Some(text.into_owned())
+}
+
+/// Looks a message up in the catalog for `locale`, falling back to English and then to the id
+/// itself, so a missing translation never ends up as an empty message. Use [`crate::tr`].
+pub fn message(locale: &str, id: &str, args: Option<&FluentArgs>) -> String {
+    format(resolve(locale), id, args)
+        .or_else(|| format(DEFAULT_LOCALE, id, args))
+        .unwrap_or_else(|| {
+            warn!("Missing message {id}");
+            id.to_string()
+        })
+}
+
+/// `tr!(locale, "shared-by", name = author)` formats a message from the catalogs.
+#[macro_export]
+macro_rules! tr {
+    ($locale:expr, $id:expr) => {
+        $crate::i18n::message($locale, $id, None)
+    };
+    ($locale:expr, $id:expr, $($key:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($key), $value);)+
+        $crate::i18n::message($locale, $id, Some(&args))
+    }};
+}
+
+/// Translations of `id` from every catalog but the default one, which is what the
+/// `register` functions are written in.
+fn localizations(id: &str) -> Option<Value> {
+    let translations = LOCALES
+        .iter()
+        .filter(|(locale, ..)| *locale != DEFAULT_LOCALE)
+        .filter_map(|(locale, ..)| {
+            Some((locale.to_string(), Value::String(format(locale, id, None)?)))
+        })
+        .collect::<Map<_, _>>();
+
+    (!translations.is_empty()).then_some(Value::Object(translations))
+}
+
+fn localize_option(option: &mut Value, parent: &str) {
+    let Some(name) = option.get("name").and_then(Value::as_str) else {
+        return;
+    };
+    let id = format!("{parent}-{name}");
+
+    let Value::Object(option) = option else {
+        return;
+    };
+
+    if let Some(names) = localizations(&format!("{id}-name")) {
+        option.insert("name_localizations".to_string(), names);
+    }
+
+    if let Some(descriptions) = localizations(&format!("{id}-description")) {
+        option.insert("description_localizations".to_string(), descriptions);
+    }
+
+    if let Some(Value::Array(choices)) = option.get_mut("choices") {
+        for choice in choices {
+            localize_choice(choice, &id);
+        }
+    }
+
+    if let Some(Value::Array(options)) = option.get_mut("options") {
+        for sub_option in options {
+            localize_option(sub_option, &id);
+        }
+    }
+}
+
+/// Choices are looked up by their value, as `<option id>-choice-<value>`.
+fn localize_choice(choice: &mut Value, option: &str) {
+    let Some(value) = choice.get("value").and_then(Value::as_str) else {
+        return;
+    };
+
+    if let Some(names) = localizations(&format!("{option}-choice-{value}")) {
+        if let Value::Object(choice) = choice {
+            choice.insert("name_localizations".to_string(), names);
+        }
+    }
+}
+
+/// Adds the translated names and descriptions of a command and all of its options, looked
+/// up as `command-<name>[-<option>...]-name` and `-description`, and the names of their choices.
+pub fn localize_command(command: &mut CreateApplicationCommand) -> &mut CreateApplicationCommand {
+    let Some(name) = command.0.get("name").and_then(Value::as_str) else {
+        return command;
+    };
+    let id = format!("command-{name}");
+
+    if let Some(names) = localizations(&format!("{id}-name")) {
+        command.0.insert("name_localizations", names);
+    }
+
+    if let Some(descriptions) = localizations(&format!("{id}-description")) {
+        command.0.insert("description_localizations", descriptions);
+    }
+
+    if let Some(Value::Array(options)) = command.0.get_mut("options") {
+        for option in options {
+            localize_option(option, &id);
+        }
+    }
+
+    command
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Ids of the messages in a catalog, they are the only lines that start with a letter.
+    fn messages(source: &str) -> Vec<&str> {
+        if let Err((_, errors)) = FluentResource::try_new(source.to_string()) {
+            panic!("catalog has errors: {errors:?}");
+        }
+
+        source
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_lowercase()))
+            .filter_map(|line| line.split_once(" =").map(|(id, _)| id))
+            .collect()
+    }
+
+    #[test]
+    fn catalogs_parse() {
+        for (locale, _, source) in LOCALES {
+            assert!(!messages(source).is_empty(), "{locale} is empty");
+        }
+    }
+
+    #[test]
+    fn translations_exist_in_english() {
+        let english = messages(LOCALES[0].2);
+
+        for (locale, _, source) in &LOCALES[1..] {
+            // Command names and descriptions are English in their register functions.
+            for id in messages(source)
+                .into_iter()
+                .filter(|id| !id.starts_with("command-"))
+            {
+                assert!(english.contains(&id), "{locale} has {id}, English doesn't");
+            }
+        }
+    }
+
+    #[test]
+    fn resolves_regions_and_falls_back() {
+        assert_eq!(resolve("de"), "de");
+        assert_eq!(resolve("es-419"), "es-ES");
+        assert_eq!(resolve("en-GB"), "en-US");
+        assert_eq!(resolve("ja"), DEFAULT_LOCALE);
+    }
+
+    #[test]
+    fn choices_are_localized() {
+        let mut command = CreateApplicationCommand::default();
+        crate::commands::history::register(&mut command);
+        localize_command(&mut command);
+
+        let options = command.0["options"].as_array().unwrap();
+        let kind = options
+            .iter()
+            .find(|option| option["name"] == "type")
+            .unwrap();
+        let song = kind["choices"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|choice| choice["value"] == "so")
+            .unwrap();
+
+        assert_eq!(song["name"], "Song");
+        assert_eq!(song["name_localizations"]["de"], "Song");
+        assert_eq!(song["name_localizations"]["es-ES"], "Canción");
+    }
+}
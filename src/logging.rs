@@ -0,0 +1,30 @@
+use tracing_subscriber::{
+    fmt::format::FmtSpan, layer::SubscriberExt, util::SubscriberInitExt, EnvFilter,
+};
+
+const DEFAULT_FILTER: &str = "cidar=trace";
+
+/// Sets up logging from `LOG_FORMAT` (`full`, `pretty` or `json`) and `LOG_FILTER`,
+/// and forwards events and spans to sentry as breadcrumbs and transactions.
+pub fn init() {
+    let filter = std::env::var("LOG_FILTER").unwrap_or(DEFAULT_FILTER.to_string());
+    let filter = EnvFilter::try_new(&filter).unwrap_or_else(|e| {
+        eprintln!("Invalid LOG_FILTER {filter:?} ({e}), using {DEFAULT_FILTER}");
+        EnvFilter::new(DEFAULT_FILTER)
+    });
+
+    // Closing spans logs how long they took, which gives us upstream timings for free.
+    let fmt = tracing_subscriber::fmt::layer().with_span_events(FmtSpan::CLOSE);
+
+    let registry = tracing_subscriber::registry()
+        .with(filter)
+        .with(sentry::integrations::tracing::layer());
+
+    match std::env::var("LOG_FORMAT").unwrap_or_default().as_str() {
+        "json" => registry
+            .with(fmt.json().with_current_span(true).with_span_list(true))
+            .init(),
+        "pretty" => registry.with(fmt.pretty()).init(),
+        _ => registry.with(fmt).init(),
+    }
+}
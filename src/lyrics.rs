@@ -0,0 +1,542 @@
+//! Apple Music serves lyrics as TTML documents. This turns them into something we can show
+//! in discord, or export as LRC and plain text.
+
+use std::{fmt::Write, time::Duration};
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum TtmlError {
+    #[error("could not read the document")]
+    Xml(#[from] quick_xml::Error),
+    #[error("document is not ttml")]
+    NotTtml,
+}
+
+/// How precise the timestamps of a document are, apple tells us through `itunes:timing`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Timing {
+    #[default]
+    None,
+    Line,
+    Word,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Agent {
+    pub id: String,
+    /// "person", "group" or "other".
+    pub kind: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Syllable {
+    pub begin: Option<Duration>,
+    pub end: Option<Duration>,
+    /// Includes the space after it if there is one, so joining syllables gives back the line.
+    pub text: String,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub begin: Option<Duration>,
+    pub end: Option<Duration>,
+    /// Id of the agent singing this line, see [`Lyrics::agent`].
+    pub agent: Option<String>,
+    /// Song part like "Verse" or "Chorus", from the surrounding div.
+    pub part: Option<String>,
+    /// Which div the line came from, lines of the same section belong together.
+    pub section: usize,
+    pub text: String,
+    /// Only filled for word synced lyrics.
+    pub syllables: Vec<Syllable>,
+    /// Background vocals sung over the line, e.g. "(ooh)".
+    pub background: Vec<Syllable>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Lyrics {
+    pub timing: Timing,
+    pub language: Option<String>,
+    pub length: Option<Duration>,
+    pub agents: Vec<Agent>,
+    pub songwriters: Vec<String>,
+    pub lines: Vec<Line>,
+}
+
+/// Reads a TTML time expression, apple sends "12.345", "1:02.345", "1:02:03.456" and
+/// sometimes "12.345s".
+fn clock(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Some(millis) = value.strip_suffix("ms") {
+        return Some(Duration::from_secs_f64(
+            millis.parse::<f64>().ok()? / 1000.0,
+        ));
+    }
+
+    let mut seconds = 0.0;
+    for part in value.trim_end_matches('s').split(':') {
+        seconds = seconds * 60.0 + part.parse::<f64>().ok()?;
+    }
+
+    (seconds.is_finite() && seconds >= 0.0).then(|| Duration::from_secs_f64(seconds))
+}
+
+/// Looks an attribute up by its local name, so we don't care which prefix apple used.
+fn attribute(element: &BytesStart, name: &str) -> Option<String> {
+    element
+        .attributes()
+        .flatten()
+        .find(|attribute| attribute.key.local_name().as_ref() == name.as_bytes())
+        .and_then(|attribute| attribute.unescape_value().ok())
+        .map(|value| value.into_owned())
+}
+
+fn joined(syllables: &[Syllable]) -> String {
+    syllables
+        .iter()
+        .map(|syllable| syllable.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// What a span we are inside of is for.
+enum Span {
+    Syllable(Syllable),
+    Background,
+    Other,
+}
+
+#[derive(Default)]
+struct Parser {
+    lyrics: Lyrics,
+    /// Local names of the open elements.
+    path: Vec<String>,
+    spans: Vec<Span>,
+    line: Option<Line>,
+    part: Option<String>,
+    section: usize,
+}
+
+impl Parser {
+    fn in_background(&self) -> bool {
+        self.spans
+            .iter()
+            .any(|span| matches!(span, Span::Background))
+    }
+
+    fn start(&mut self, element: &BytesStart) {
+        let name = String::from_utf8_lossy(element.local_name().as_ref()).into_owned();
+
+        match name.as_str() {
+            "tt" => {
+                self.lyrics.language = attribute(element, "lang");
+                self.lyrics.timing = match attribute(element, "timing").as_deref() {
+                    Some("Word") => Timing::Word,
+                    Some("Line") => Timing::Line,
+                    Some(_) => Timing::None,
+                    // Older documents leave it out, we work it out from the lines instead.
+                    None => Timing::Line,
+                };
+            }
+            "agent" => self.lyrics.agents.push(Agent {
+                id: attribute(element, "id").unwrap_or_default(),
+                kind: attribute(element, "type"),
+                name: None,
+            }),
+            "body" => self.lyrics.length = attribute(element, "dur").and_then(|dur| clock(&dur)),
+            "div" => {
+                self.section += 1;
+                self.part = attribute(element, "songPart");
+            }
+            "p" => {
+                self.line = Some(Line {
+                    begin: attribute(element, "begin").and_then(|begin| clock(&begin)),
+                    end: attribute(element, "end").and_then(|end| clock(&end)),
+                    agent: attribute(element, "agent"),
+                    part: self.part.clone(),
+                    section: self.section,
+                    ..Default::default()
+                })
+            }
+            "span" if self.line.is_some() => {
+                let span = if attribute(element, "role").as_deref() == Some("x-bg") {
+                    Span::Background
+                } else if let Some(begin) = attribute(element, "begin") {
+                    Span::Syllable(Syllable {
+                        begin: clock(&begin),
+                        end: attribute(element, "end").and_then(|end| clock(&end)),
+                        text: String::new(),
+                    })
+                } else {
+                    Span::Other
+                };
+                self.spans.push(span);
+            }
+            _ => {}
+        }
+
+        self.path.push(name);
+    }
+
+    fn end(&mut self) {
+        let Some(name) = self.path.pop() else {
+            return;
+        };
+
+        match name.as_str() {
+            "div" => self.part = None,
+            "p" => {
+                let Some(mut line) = self.line.take() else {
+                    return;
+                };
+
+                if line.syllables.is_empty() {
+                    line.text = line.text.trim().to_string();
+                } else {
+                    line.text = joined(&line.syllables);
+                }
+
+                self.lyrics.lines.push(line);
+                self.spans.clear();
+            }
+            "span" => {
+                let Some(span) = self.spans.pop() else {
+                    return;
+                };
+
+                if let (Span::Syllable(syllable), Some(line)) = (span, self.line.as_mut()) {
+                    if self
+                        .spans
+                        .iter()
+                        .any(|span| matches!(span, Span::Background))
+                    {
+                        line.background.push(syllable);
+                    } else {
+                        line.syllables.push(syllable);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        match self.path.last().map(String::as_str) {
+            Some("name") if self.path.iter().any(|name| name == "agent") => {
+                if let Some(agent) = self.lyrics.agents.last_mut() {
+                    agent.name = Some(text.trim().to_string());
+                }
+                return;
+            }
+            Some("songwriter") => {
+                self.lyrics.songwriters.push(text.trim().to_string());
+                return;
+            }
+            _ => {}
+        }
+
+        let in_background = self.in_background();
+        let Some(line) = self.line.as_mut() else {
+            return;
+        };
+
+        match self.spans.last_mut() {
+            Some(Span::Syllable(syllable)) => syllable.text.push_str(text),
+            // Whitespace between syllables, the previous one ends a word.
+            _ if text.trim().is_empty() => {
+                let syllables = match in_background {
+                    true => &mut line.background,
+                    false => &mut line.syllables,
+                };
+
+                match syllables.last_mut() {
+                    Some(syllable) => syllable.text.push(' '),
+                    None if !in_background => line.text.push_str(text),
+                    None => {}
+                }
+            }
+            _ if !in_background => line.text.push_str(text),
+            _ => {}
+        }
+    }
+}
+
+/// Parses an Apple Music TTML document.
+pub fn parse(ttml: &str) -> Result<Lyrics, TtmlError> {
+    let mut reader = Reader::from_str(ttml);
+    let mut parser = Parser::default();
+    let mut seen_root = false;
+
+    loop {
+        match reader.read_event()? {
+            Event::Start(element) => {
+                seen_root |= element.local_name().as_ref() == b"tt";
+                parser.start(&element);
+            }
+            Event::Empty(element) => {
+                seen_root |= element.local_name().as_ref() == b"tt";
+                parser.start(&element);
+                parser.end();
+            }
+            Event::End(_) => parser.end(),
+            Event::Text(text) => parser.text(&text.unescape()?),
+            Event::CData(text) => parser.text(&String::from_utf8_lossy(&text)),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    if !seen_root {
+        return Err(TtmlError::NotTtml);
+    }
+
+    let mut lyrics = parser.lyrics;
+
+    // Don't promise timestamps we don't have.
+    if lyrics.timing != Timing::None && lyrics.lines.iter().any(|line| line.begin.is_none()) {
+        lyrics.timing = Timing::None;
+    }
+    if lyrics.timing == Timing::Word && lyrics.lines.iter().all(|line| line.syllables.is_empty()) {
+        lyrics.timing = Timing::Line;
+    }
+
+    Ok(lyrics)
+}
+
+/// Formats a timestamp the way LRC wants it, "mm:ss.xx".
+fn lrc_time(time: Duration) -> String {
+    let centis = time.as_millis() / 10;
+    format!(
+        "{:02}:{:02}.{:02}",
+        centis / 6000,
+        (centis / 100) % 60,
+        centis % 100
+    )
+}
+
+impl Lyrics {
+    pub fn synced(&self) -> bool {
+        self.timing != Timing::None
+    }
+
+    pub fn agent(&self, id: &str) -> Option<&Agent> {
+        self.agents.iter().find(|agent| agent.id == id)
+    }
+}
+
+// The bot only shows lyrics in embeds for now, exports are for tooling built on this module.
+impl Lyrics {
+    /// Just the words, with an empty line between sections.
+    #[allow(dead_code)]
+    pub fn to_plain(&self) -> String {
+        let mut plain = String::new();
+
+        for (i, line) in self.lines.iter().enumerate() {
+            if i > 0 && self.lines[i - 1].section != line.section {
+                plain.push('\n');
+            }
+            plain.push_str(&line.text);
+            plain.push('\n');
+        }
+
+        plain
+    }
+
+    /// Line synced LRC, `None` if the lyrics have no timestamps.
+    #[allow(dead_code)]
+    pub fn to_lrc(&self) -> Option<String> {
+        self.lrc(false)
+    }
+
+    /// LRC with a timestamp for every word, falls back to plain LRC for line synced lyrics.
+    #[allow(dead_code)]
+    pub fn to_enhanced_lrc(&self) -> Option<String> {
+        self.lrc(self.timing == Timing::Word)
+    }
+
+    fn lrc(&self, words: bool) -> Option<String> {
+        if !self.synced() {
+            return None;
+        }
+
+        let mut lrc = String::new();
+
+        if let Some(language) = &self.language {
+            let _ = writeln!(lrc, "[la:{language}]");
+        }
+        if !self.songwriters.is_empty() {
+            let _ = writeln!(lrc, "[au:{}]", self.songwriters.join(", "));
+        }
+        if let Some(length) = self.length {
+            // Just minutes and seconds, the minutes don't stop at 99.
+            let seconds = length.as_secs();
+            let _ = writeln!(lrc, "[length:{:02}:{:02}]", seconds / 60, seconds % 60);
+        }
+
+        for line in &self.lines {
+            let _ = write!(lrc, "[{}]", lrc_time(line.begin?));
+
+            if words && !line.syllables.is_empty() {
+                for syllable in &line.syllables {
+                    if let Some(begin) = syllable.begin {
+                        let _ = write!(lrc, "<{}>", lrc_time(begin));
+                    }
+                    lrc.push_str(&syllable.text);
+                }
+                if let Some(end) = line.end {
+                    let _ = write!(lrc, "<{}>", lrc_time(end));
+                }
+            } else {
+                lrc.push_str(&line.text);
+            }
+
+            lrc.push('\n');
+        }
+
+        Some(lrc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORD: &str = include_str!("../fixtures/lyrics/word.ttml");
+    const LINE: &str = include_str!("../fixtures/lyrics/line.ttml");
+    const UNSYNCED: &str = include_str!("../fixtures/lyrics/unsynced.ttml");
+
+    fn secs(seconds: f64) -> Option<Duration> {
+        Some(Duration::from_secs_f64(seconds))
+    }
+
+    #[test]
+    fn clock_formats() {
+        assert_eq!(clock("12.345"), secs(12.345));
+        assert_eq!(clock("1:02.5"), secs(62.5));
+        assert_eq!(clock("1:02:03.25"), secs(3723.25));
+        assert_eq!(clock("4.5s"), secs(4.5));
+        assert_eq!(clock("250ms"), secs(0.25));
+        assert_eq!(clock("soon"), None);
+    }
+
+    #[test]
+    fn word_synced() {
+        let lyrics = parse(WORD).unwrap();
+
+        assert_eq!(lyrics.timing, Timing::Word);
+        assert_eq!(lyrics.language.as_deref(), Some("en"));
+        assert_eq!(lyrics.length, secs(201.0));
+        assert_eq!(lyrics.songwriters, vec!["Jane Doe", "John Roe"]);
+        assert_eq!(lyrics.lines.len(), 3);
+
+        let first = &lyrics.lines[0];
+        assert_eq!(first.text, "Hello world");
+        assert_eq!(first.begin, secs(10.1));
+        assert_eq!(first.end, secs(12.3));
+        assert_eq!(first.part.as_deref(), Some("Verse"));
+        assert_eq!(
+            first
+                .syllables
+                .iter()
+                .map(|syllable| syllable.text.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Hel", "lo ", "world"]
+        );
+        assert_eq!(first.syllables[1].begin, secs(10.5));
+        assert_eq!(joined(&first.background), "(ooh ah)");
+    }
+
+    #[test]
+    fn agents() {
+        let lyrics = parse(WORD).unwrap();
+
+        assert_eq!(lyrics.agents.len(), 2);
+        assert_eq!(lyrics.lines[0].agent.as_deref(), Some("v1"));
+        assert_eq!(lyrics.lines[2].agent.as_deref(), Some("v2"));
+
+        let duet = lyrics.agent("v2").unwrap();
+        assert_eq!(duet.kind.as_deref(), Some("person"));
+        assert_eq!(duet.name.as_deref(), Some("Guest Singer"));
+        assert_eq!(lyrics.agent("v1").unwrap().name, None);
+    }
+
+    #[test]
+    fn line_synced() {
+        let lyrics = parse(LINE).unwrap();
+
+        assert_eq!(lyrics.timing, Timing::Line);
+        assert_eq!(lyrics.lines.len(), 4);
+        assert_eq!(lyrics.lines[0].text, "First line & more");
+        assert_eq!(lyrics.lines[3].begin, secs(75.0));
+        assert!(lyrics.lines.iter().all(|line| line.syllables.is_empty()));
+        assert_eq!(lyrics.lines[2].section, 2);
+    }
+
+    #[test]
+    fn unsynced() {
+        let lyrics = parse(UNSYNCED).unwrap();
+
+        assert_eq!(lyrics.timing, Timing::None);
+        assert!(!lyrics.synced());
+        assert_eq!(lyrics.lines.len(), 3);
+        assert_eq!(lyrics.to_lrc(), None);
+    }
+
+    #[test]
+    fn plain_export() {
+        let lyrics = parse(LINE).unwrap();
+
+        assert_eq!(
+            lyrics.to_plain(),
+            "First line & more\nSecond line\n\nChorus goes here\nAnd ends here\n"
+        );
+    }
+
+    #[test]
+    fn lrc_export() {
+        let lyrics = parse(LINE).unwrap();
+
+        assert_eq!(
+            lyrics.to_lrc().unwrap(),
+            "[la:en]\n\
+             [length:02:30]\n\
+             [00:05.00]First line & more\n\
+             [00:09.50]Second line\n\
+             [01:02.25]Chorus goes here\n\
+             [01:15.00]And ends here\n"
+        );
+    }
+
+    #[test]
+    fn enhanced_lrc_export() {
+        let lyrics = parse(WORD).unwrap();
+        let lrc = lyrics.to_enhanced_lrc().unwrap();
+
+        assert!(lrc.starts_with("[la:en]\n[au:Jane Doe, John Roe]\n[length:03:21]\n"));
+        assert!(lrc.contains("[00:10.10]<00:10.10>Hel<00:10.50>lo <00:11.20>world<00:12.30>\n"));
+
+        // Line synced lyrics have no words to time.
+        let line = parse(LINE).unwrap();
+        assert_eq!(line.to_enhanced_lrc(), line.to_lrc());
+    }
+
+    #[test]
+    fn long_lengths() {
+        let mut lyrics = parse(LINE).unwrap();
+        lyrics.length = secs(6_125.0);
+
+        assert!(lyrics.to_lrc().unwrap().contains("[length:102:05]\n"));
+    }
+
+    #[test]
+    fn not_ttml() {
+        assert!(matches!(parse("<html></html>"), Err(TtmlError::NotTtml)));
+        assert!(parse("<tt><body><p>unclosed</div></tt>").is_err());
+    }
+}
@@ -1,25 +1,42 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use serenity::async_trait;
+use serenity::client::bridge::gateway::event::ShardStageUpdateEvent;
 use serenity::framework::StandardFramework;
-use serenity::model::application::component::ButtonStyle;
+use serenity::gateway::ConnectionStage;
 use serenity::model::gateway::Ready;
-use serenity::model::prelude::command::Command;
-use serenity::model::prelude::{Interaction, InteractionResponseType, Message};
+use serenity::model::id::GuildId;
+use serenity::model::prelude::{Interaction, Message};
 use serenity::model::Timestamp;
 use serenity::prelude::*;
 
 use dotenv::dotenv;
 use log::*;
 use regex::Regex;
+use tracing::{field, info_span, Instrument, Span};
 
+mod alternates;
 mod api;
+mod cache;
+mod cider_links;
 mod commands;
+mod config;
 mod conversion;
+mod guild;
+mod health;
+mod i18n;
+mod logging;
+mod lyrics;
+mod ranking;
+mod shutdown;
+mod similar;
+mod storage;
 mod updater;
 mod util;
 mod vpath;
@@ -31,68 +48,137 @@ const DEBUG_CHANNEL: u64 = 1133927653074796555;
 
 struct Handler {
     client: Arc<RwLock<reqwest::Client>>,
-    api: api::AppleMusicApi,
+    api: Arc<api::AppleMusicApi>,
     url_regex: Regex,
     apple_regex: Regex,
     spotify_regex: Regex,
+    health: Arc<health::Health>,
+    shutdown: Arc<shutdown::Shutdown>,
+    storage: Arc<storage::Storage>,
+    config: Arc<RwLock<config::Config>>,
+    // Shards we already run a presence updater for, ready fires again on every reconnect.
+    presence_shards: Mutex<HashSet<u64>>,
+    digest_started: AtomicBool,
+    releases_started: AtomicBool,
+    commands_synced: AtomicBool,
+    // Local copy of the maintenance flag in storage, kept in sync by the runtime sync.
+    maintenance: Arc<AtomicBool>,
+    // Last time we told a channel about maintenance, so we don't answer every single link.
+    maintenance_notices: Mutex<HashMap<u64, Instant>>,
+    // Recommendation lists behind the similar button, keyed by storefront, kind and id.
+    similar: cache::TtlCache<Vec<similar::Recommendation>>,
+    // Artists shared per guild for the /history autocomplete.
+    shared_artists: cache::TtlCache<Vec<String>>,
+    started: Instant,
+}
+
+impl Handler {
+    /// The guild's settings and the language to post in, defaults outside of guilds.
+    async fn guild_settings(
+        &self,
+        ctx: &Context,
+        guild_id: Option<GuildId>,
+    ) -> (guild::GuildConfig, String) {
+        let Some(guild_id) = guild_id else {
+            return (Default::default(), i18n::DEFAULT_LOCALE.to_string());
+        };
+
+        let config = self
+            .storage
+            .guild_config(guild_id.0)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Unable to read the config for guild {guild_id}: {e}");
+                Default::default()
+            });
+
+        let locale = i18n::guild_locale(ctx, guild_id.0, &config);
+        (config, locale)
+    }
 }
 
 #[async_trait]
 impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         info!("{} is connected", ready.user.name);
-        tokio::task::spawn(updater::status_updater(ctx.clone()));
+        self.health.set_shard(ctx.shard_id, true).await;
+
+        if self.presence_shards.lock().await.insert(ctx.shard_id) {
+            tokio::task::spawn(updater::status_updater(
+                ctx.clone(),
+                self.storage.clone(),
+                self.config.clone(),
+            ));
+        }
 
-        // Setup commands
-        let _ = Command::create_global_application_command(&ctx.http, |command| {
-            commands::about::register(command)
-        })
-        .await;
+        // One digest poster per process is enough, it covers every guild in our cache.
+        if !self.digest_started.swap(true, Ordering::SeqCst) {
+            tokio::task::spawn(updater::digest_poster(ctx.clone(), self.storage.clone()));
+        }
 
-        let _ = Command::create_global_application_command(&ctx.http, |command| {
-            commands::convert::register(command)
-        })
-        .await;
+        // Setup commands, once per process is enough since they don't change at runtime.
+        if !self.commands_synced.swap(true, Ordering::SeqCst) {
+            if let Err(why) = commands::sync(&ctx.http, &self.storage, false).await {
+                error!("Unable to register commands: {why}");
+                self.commands_synced.store(false, Ordering::SeqCst);
+            }
+        }
+    }
+
+    // The poller only posts for guilds in our cache, started on ready it would skip all of them
+    // in its first round.
+    async fn cache_ready(&self, ctx: Context, _guilds: Vec<GuildId>) {
+        if !self.releases_started.swap(true, Ordering::SeqCst) {
+            tokio::task::spawn(updater::release_poller(
+                ctx,
+                self.storage.clone(),
+                self.api.clone(),
+            ));
+        }
+    }
+
+    async fn shard_stage_update(&self, _ctx: Context, event: ShardStageUpdateEvent) {
+        self.health
+            .set_shard(event.shard_id.0, event.new == ConnectionStage::Connected)
+            .await;
     }
 
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
-        if let Interaction::ApplicationCommand(command) = interaction {
-            // Only allow the debug channel in debug mode.
-            #[cfg(debug_assertions)]
-            if command.channel_id.0 != DEBUG_CHANNEL {
-                return;
-            }
+        let channel_id = match &interaction {
+            Interaction::ApplicationCommand(command) => command.channel_id,
+            Interaction::Autocomplete(autocomplete) => autocomplete.channel_id,
+            Interaction::MessageComponent(component) => component.channel_id,
+            _ => return,
+        };
 
-            // In release builds, make sure to exclude the debug channel.
-            #[cfg(not(debug_assertions))]
-            if command.channel_id.0 == DEBUG_CHANNEL {
-                return;
-            }
+        // Only allow the debug channel in debug mode.
+        #[cfg(debug_assertions)]
+        if channel_id.0 != DEBUG_CHANNEL {
+            return;
+        }
 
-            let _ = command
-                .create_interaction_response(&ctx.http, |response| {
-                    response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|message| message.content("processing..."))
-                })
-                .await;
-
-            let content = match command.data.name.as_str() {
-                "about" => commands::about::run(&command.data.options),
-                "convert" => {
-                    commands::convert::run(&command.data.options, &self.api, &self.url_regex)
-                        .await
-                        .unwrap_or_else(|err| err.to_string())
-                }
-                _ => "not implemented".to_string(),
-            };
+        // In release builds, make sure to exclude the debug channel.
+        #[cfg(not(debug_assertions))]
+        if channel_id.0 == DEBUG_CHANNEL {
+            return;
+        }
 
-            if let Err(why) = command
-                .edit_original_interaction_response(&ctx.http, |response| response.content(content))
-                .await
-            {
-                warn!("Cannot respond to slash command: {why}");
+        // Don't start anything new while we are shutting down.
+        let Some(_in_flight) = self.shutdown.begin() else {
+            return;
+        };
+
+        match interaction {
+            Interaction::ApplicationCommand(command) => {
+                commands::respond(self, &ctx, &command).await
             }
+            Interaction::Autocomplete(autocomplete) => {
+                commands::autocomplete(self, &ctx, &autocomplete).await
+            }
+            Interaction::MessageComponent(component) => {
+                commands::component(self, &ctx, &component).await
+            }
+            _ => {}
         }
     }
 
@@ -115,6 +201,11 @@ impl EventHandler for Handler {
         }
 
         if self.url_regex.is_match(&new_message.content) {
+            // Hold a slot until the conversion is sent, so shutdown waits for us.
+            let Some(_in_flight) = self.shutdown.begin() else {
+                return;
+            };
+
             let mut url = match self.url_regex.find(&new_message.content) {
                 Some(url) => url.as_str().to_string(),
                 None => return,
@@ -125,117 +216,188 @@ impl EventHandler for Handler {
                 return;
             }
 
-            // Try to obtain an apple music link from song.link, for now this service is free
-            // in alpha. So this may change / not work in the future.
-            if self.spotify_regex.is_match(&url) {
-                // nifty trick to avoid panics using let-else statements
-                // and add some context to the error, even if it's fugly
+            let (guild_config, locale) = self.guild_settings(&ctx, new_message.guild_id).await;
 
-                let Ok(response) = self
-                    .client
-                    .read()
-                    .await
-                    .get(format!("https://api.song.link/v1-alpha.1/links?url={url}"))
-                    .send()
-                    .await
-                else {
-                    warn!("failed to send request to song.link api");
+            if self.maintenance.load(Ordering::SeqCst) {
+                let mut notices = self.maintenance_notices.lock().await;
+                let recently = notices
+                    .get(&new_message.channel_id.0)
+                    .is_some_and(|at| at.elapsed() < Duration::from_secs(60 * 10));
+
+                if !recently {
+                    notices.insert(new_message.channel_id.0, Instant::now());
+                    let _ = new_message
+                        .reply(&ctx.http, tr!(&locale, "maintenance"))
+                        .await;
+                }
+                return;
+            }
+
+            let span = info_span!(
+                "conversion",
+                message_id = new_message.id.0,
+                guild_id = new_message.guild_id.map(|id| id.0),
+                channel_id = new_message.channel_id.0,
+                source_url = %url,
+                media_type = field::Empty,
+                outcome = "failed",
+            );
+
+            async {
+                let platform = if self.spotify_regex.is_match(&url) {
+                    conversion::Platform::Spotify
+                } else {
+                    conversion::Platform::AppleMusic
+                };
+
+                // Try to obtain an apple music link from song.link, for now this service is free
+                // in alpha. So this may change / not work in the future.
+                if platform == conversion::Platform::Spotify {
+                    // nifty trick to avoid panics using let-else statements
+                    // and add some context to the error, even if it's fugly
+
+                    let Ok(response) = self
+                        .client
+                        .read()
+                        .await
+                        .get(format!("https://api.song.link/v1-alpha.1/links?url={url}"))
+                        .send()
+                        .instrument(info_span!("song_link"))
+                        .await
+                    else {
+                        warn!("failed to send request to song.link api");
+                        return;
+                    };
+
+                    let Ok(serialized) = response.json::<Value>().await else {
+                        warn!("failed to serialize response from song.link api");
+                        return;
+                    };
+
+                    self.health.upstream_ok(health::Upstream::SongLink).await;
+
+                    let Some(amurl) =
+                        serialized.get_value_by_path("linksByPlatform.appleMusic.url")
+                    else {
+                        warn!("failed to get apple music link from song.link");
+                        return;
+                    };
+
+                    url = match amurl.as_str() {
+                        Some(url) => url.to_string(),
+                        None => return,
+                    };
+                }
+
+                let Ok(parsed_url) = Url::parse(&url) else {
+                    warn!("failed to parse url");
                     return;
                 };
 
-                let Ok(serialized) = response.json::<Value>().await else {
-                    warn!("failed to serialize response from song.link api");
+                let mut query: HashMap<String, String> = HashMap::new();
+
+                // Turn the pairs into a hash map, so we can quickly index it.
+                for (key, value) in parsed_url.query_pairs() {
+                    // Insert the key-value pair into the HashMap
+                    query.insert(key.to_string(), value.to_string());
+                }
+
+                let longer = url.replace("https://", "");
+
+                let items = longer.split('/').collect::<Vec<&str>>();
+                let Some(storefront) = items.get(1) else {
+                    warn!("Unable to obtain storefront from URL");
                     return;
                 };
 
-                let Some(amurl) = serialized.get_value_by_path("linksByPlatform.appleMusic.url")
+                let Some(information) = conversion::get_information(
+                    &self.api,
+                    &parsed_url,
+                    storefront,
+                    &query,
+                    &new_message,
+                    &locale,
+                    &guild_config.cider_links,
+                )
+                .await
                 else {
-                    warn!("failed to get apple music link from song.link");
+                    warn!("Unable to obtain informaiton for the embed");
                     return;
                 };
 
-                url = match amurl.as_str() {
-                    Some(url) => url.to_string(),
-                    None => return,
+                let record = conversion::ConversionRecord {
+                    timestamp: Timestamp::now().unix_timestamp(),
+                    guild_id: new_message.guild_id.map(|id| id.0),
+                    author_id: new_message.author.id.0,
+                    author_name: new_message.author.name.clone(),
+                    channel_id: new_message.channel_id.0,
+                    message_id: new_message.id.0,
+                    platform,
+                    media_type: information.media_type,
+                    catalog_id: Some(information.catalog_id.clone()),
+                    title: information.title.clone(),
+                    artist: information.artist.clone(),
+                    artist_id: information.artist_id.clone(),
+                    album: information.album.clone(),
+                    album_id: information.album_id.clone(),
+                    url: Some(url.clone()),
                 };
-            }
-
-            let Ok(parsed_url) = Url::parse(&url) else {
-                warn!("failed to parse url");
-                return;
-            };
 
-            let mut query: HashMap<String, String> = HashMap::new();
-
-            // Turn the pairs into a hash map, so we can quickly index it.
-            for (key, value) in parsed_url.query_pairs() {
-                // Insert the key-value pair into the HashMap
-                query.insert(key.to_string(), value.to_string());
-            }
+                let Ok(mut sent) = new_message
+                    .channel_id
+                    .send_message(&ctx.http, |m| {
+                        m.embed(|e| information.embed(e, &locale, &guild_config))
+                            .components(|c| {
+                                information.buttons(c, &locale, &guild_config.cider_links)
+                            })
+                    })
+                    .await
+                else {
+                    error!("Unable to send message, ");
+                    return;
+                };
 
-            let longer = url.replace("https://", "");
+                // Is not that important, can fail.
+                let _ = new_message.suppress_embeds(&ctx.http).await;
 
-            let items = longer.split('/').collect::<Vec<&str>>();
-            let Some(storefront) = items.get(1) else {
-                warn!("Unable to obtain storefront from URL");
-                return;
-            };
+                // Update the conversions
+                let _ = self.storage.increment_conversion().await; // tbh i dont care if this failes as the program itself does not depend on it
 
-            let Some(information) = conversion::get_information(
-                &self.api,
-                &parsed_url,
-                storefront,
-                &query,
-                &new_message,
-            )
-            .await
-            else {
-                warn!("Unable to obtain informaiton for the embed");
-                return;
-            };
+                if let Err(e) = self.storage.record_conversion(&record).await {
+                    warn!("Unable to record conversion: {e}");
+                }
 
-            let modded = url.replace("https://", "");
-
-            let play_link = format!("https://cider.sh/p?{}", modded);
-            let view_link = format!("https://cider.sh/o?{}", modded);
-
-            let Ok(_) = new_message
-                .channel_id
-                .send_message(&ctx.http, |m| {
-                    m.embed(|e| {
-                        e.title(information.title)
-                            .url(information.url)
-                            .thumbnail(information.artwork)
-                            .description(information.description)
-                            .footer(|f| f.text(information.footer))
-                            .timestamp(Timestamp::now())
-                    })
-                    .components(|c| {
-                        c.create_action_row(|r| {
-                            r.create_button(|b| {
-                                b.label("Play in Cider")
-                                    .style(ButtonStyle::Link)
-                                    .url(play_link)
+                // Finding other versions takes a few more requests, the embed shouldn't wait for
+                // them. The menu gets added once we know there is something to pick.
+                if information.media_type == conversion::MediaType::Song {
+                    let alternates =
+                        alternates::find(&self.api, storefront, &information.catalog_id).await;
+
+                    if !alternates.is_empty() {
+                        if let Err(e) = sent
+                            .edit(&ctx.http, |m| {
+                                m.components(|c| {
+                                    information.buttons(c, &locale, &guild_config.cider_links);
+                                    alternates::menu(
+                                        c,
+                                        &information,
+                                        &alternates,
+                                        new_message.author.id.0,
+                                        &locale,
+                                    )
+                                })
                             })
-                            .create_button(|b| {
-                                b.label("View in Cider")
-                                    .style(ButtonStyle::Link)
-                                    .url(view_link)
-                            })
-                        })
-                    })
-                })
-                .await
-            else {
-                error!("Unable to send message, ");
-                return;
-            };
-
-            // Is not that important, can fail.
-            let _ = new_message.suppress_embeds(&ctx.http).await;
+                            .await
+                        {
+                            warn!("Unable to add the alternate versions: {e}");
+                        }
+                    }
+                }
 
-            // Update the conversions
-            let _ = util::increment_conversion().await; // tbh i dont care if this failes as the program itself does not depend on it
+                Span::current().record("outcome", "converted");
+            }
+            .instrument(span)
+            .await;
         }
     }
 }
@@ -245,15 +407,43 @@ pub struct Stats {
     pub total_conversions: u64,
 }
 
+/// Reads `SHARD_RANGE` and `SHARD_TOTAL`, the range is inclusive and has to fit in the total.
+fn shard_range(range: &str, total: &str) -> Result<([u64; 2], u64), String> {
+    let (first, last) = range
+        .split_once('-')
+        .ok_or(format!("SHARD_RANGE should look like 0-3, got {range:?}"))?;
+    let first: u64 = first
+        .trim()
+        .parse()
+        .map_err(|_| format!("SHARD_RANGE start {first:?} is not a number"))?;
+    let last: u64 = last
+        .trim()
+        .parse()
+        .map_err(|_| format!("SHARD_RANGE end {last:?} is not a number"))?;
+    let total: u64 = total.trim().parse().map_err(|_| {
+        format!("Please set SHARD_TOTAL to a number when using SHARD_RANGE, got {total:?}")
+    })?;
+
+    if first > last {
+        return Err(format!("SHARD_RANGE {first}-{last} is reversed"));
+    }
+
+    if last >= total {
+        return Err(format!(
+            "SHARD_RANGE {first}-{last} goes past SHARD_TOTAL {total}, shard ids start at 0"
+        ));
+    }
+
+    Ok(([first, last], total))
+}
+
 #[tokio::main]
 async fn main() {
     // Setup dotenv just in case someone used it instead (very useful for development)
     dotenv().ok();
 
     // Setup the logger
-    tracing_subscriber::fmt()
-        .with_env_filter("cidar=trace")
-        .init();
+    logging::init();
 
     info!("Cidar launching");
 
@@ -261,8 +451,12 @@ async fn main() {
 
     info!("Starting crash governer");
 
-    let _guard = sentry::init(("https://15cf6882a0fd0152775f80dbbf4b1c4e@o4504730117865472.ingest.sentry.io/4505693108371456", sentry::ClientOptions {
+    let sentry_guard = sentry::init(("https://15cf6882a0fd0152775f80dbbf4b1c4e@o4504730117865472.ingest.sentry.io/4505693108371456", sentry::ClientOptions {
         release: sentry::release_name!(),
+        traces_sample_rate: std::env::var("SENTRY_TRACES_SAMPLE_RATE")
+            .ok()
+            .and_then(|rate| rate.parse().ok())
+            .unwrap_or(0.1),
         ..Default::default()
     }));
 
@@ -273,29 +467,144 @@ async fn main() {
         | GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::GUILD_INTEGRATIONS;
 
-    tokio::task::spawn(updater::token_updater(developer_token.clone()));
+    let health: Arc<health::Health> = Default::default();
+
+    let health_addr = std::env::var("HEALTH_ADDR")
+        .unwrap_or("0.0.0.0:8080".to_string())
+        .parse()
+        .expect("HEALTH_ADDR is not a valid socket address");
+
+    tokio::task::spawn(health::serve(health.clone(), health_addr));
+    tokio::task::spawn(updater::token_updater(
+        developer_token.clone(),
+        health.clone(),
+    ));
+
+    let shutdown: Arc<shutdown::Shutdown> = Default::default();
+
+    let storage = Arc::new(
+        storage::Storage::connect()
+            .await
+            .expect("Unable to connect to storage"),
+    );
+
+    let maintenance: Arc<AtomicBool> = Default::default();
 
     // Only use 1 client for the discord stuffs, if it causes deadlocking, create a client for every request
     let discord_reqwest_client = Arc::new(RwLock::new(reqwest::Client::new()));
 
     let handler = Handler {
         client: discord_reqwest_client.clone(),
-        api: api::AppleMusicApi {
+        api: Arc::new(api::AppleMusicApi {
             client: discord_reqwest_client.clone(),
             developer_token: developer_token.clone(),
-        },
+            health: health.clone(),
+            cache: cache::TtlCache::new(Duration::from_secs(60 * 30)),
+        }),
         url_regex: Regex::new(r"(?:(?:https?|ftp)://)?[\w/\-?=%.]+\.[\w/\-&?=%.]+").unwrap(),
         apple_regex: Regex::new(r"music.apple.com/(.+[a-z](/?)+)").unwrap(),
         spotify_regex: Regex::new(r"open.spotify.com/(.+[a-z](/?)+)").unwrap(),
+        health,
+        shutdown: shutdown.clone(),
+        storage: storage.clone(),
+        config: Arc::new(RwLock::new(config::Config::from_env())),
+        presence_shards: Default::default(),
+        digest_started: Default::default(),
+        releases_started: Default::default(),
+        commands_synced: Default::default(),
+        maintenance: maintenance.clone(),
+        maintenance_notices: Default::default(),
+        similar: cache::TtlCache::new(Duration::from_secs(60 * 60 * 6)),
+        shared_artists: cache::TtlCache::new(Duration::from_secs(60 * 5)),
+        started: Instant::now(),
     };
 
+    tokio::task::spawn(updater::runtime_sync(
+        storage.clone(),
+        handler.api.clone(),
+        maintenance,
+    ));
+
     let mut client = serenity::Client::builder(token, intents)
         .event_handler(handler)
         .framework(StandardFramework::new())
         .await
         .expect("Error creating client");
 
-    if let Err(why) = client.start().await {
+    let shutdown_timeout = Duration::from_secs(
+        std::env::var("SHUTDOWN_TIMEOUT")
+            .ok()
+            .and_then(|secs| secs.parse().ok())
+            .unwrap_or(30),
+    );
+
+    let shard_manager = client.shard_manager.clone();
+    let shutdown_storage = storage.clone();
+    tokio::task::spawn(async move {
+        shutdown::signal().await;
+
+        info!("Shutting down, waiting for in-flight conversions");
+        if !shutdown.drain(shutdown_timeout).await {
+            warn!("Gave up waiting for in-flight conversions after {shutdown_timeout:?}");
+        }
+
+        // Stats, records and guild settings are already in storage, the caches only hold
+        // catalog answers and lists we rebuild on demand. Just let the last write land.
+        shutdown_storage.flush().await;
+
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
+    // Discord requires sharding past 2500 guilds, either let it pick the shard count or run
+    // the slice of shards this process was given, e.g. SHARD_RANGE=4-7 SHARD_TOTAL=10.
+    let shard_range = std::env::var("SHARD_RANGE").ok().map(|range| {
+        let total = std::env::var("SHARD_TOTAL").unwrap_or_default();
+        shard_range(&range, &total).unwrap_or_else(|e| {
+            error!("{e}");
+            std::process::exit(1);
+        })
+    });
+
+    if shard_range.is_some() && !storage.is_shared() {
+        warn!(
+            "Running a shard range with file storage, stats will not be shared between processes"
+        );
+    }
+
+    let started = match shard_range {
+        Some((range, total)) => {
+            info!("Starting shards {}-{} of {total}", range[0], range[1]);
+            client.start_shard_range(range, total).await
+        }
+        None => client.start_autosharded().await,
+    };
+
+    if let Err(why) = started {
         error!("Client error: {:?}", why);
     }
+
+    info!("Cidar stopped, flushing crash reports");
+
+    // Dropping the guard flushes any queued events to sentry before we exit.
+    drop(sentry_guard);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shard_range_in_total() {
+        assert_eq!(shard_range("4-7", "10"), Ok(([4, 7], 10)));
+        assert_eq!(shard_range("0-0", "1"), Ok(([0, 0], 1)));
+    }
+
+    #[test]
+    fn shard_range_rejects_bad_ranges() {
+        assert!(shard_range("7-4", "10").is_err());
+        assert!(shard_range("4-10", "10").is_err());
+        assert!(shard_range("4", "10").is_err());
+        assert!(shard_range("a-b", "10").is_err());
+        assert!(shard_range("0-3", "").is_err());
+    }
 }
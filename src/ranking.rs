@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+
+/// Prefer the catalog id so renamed or duplicate titles still count as the same thing.
+pub fn key(id: &Option<String>, name: &str) -> String {
+    id.clone().unwrap_or_else(|| name.to_lowercase())
+}
+
+/// Counts entries by key, keeping the label of the first one we saw for display. Most common
+/// first, ties in label order.
+pub fn ranked(entries: impl Iterator<Item = (String, String)>) -> Vec<(String, usize)> {
+    let mut counts: HashMap<String, (String, usize)> = HashMap::new();
+    for (key, label) in entries {
+        counts.entry(key).or_insert((label, 0)).1 += 1;
+    }
+
+    let mut ranked = counts.into_values().collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked
+}
+
+/// The first `limit` entries as a numbered list, `None` when there's nothing to list.
+pub fn listed(ranked: &[(String, usize)], limit: usize) -> Option<String> {
+    if ranked.is_empty() {
+        return None;
+    }
+
+    Some(
+        ranked
+            .iter()
+            .take(limit)
+            .enumerate()
+            .map(|(i, (label, count))| format!("{}. {label} ({count})", i + 1))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(keys: &[&str]) -> Vec<(String, String)> {
+        keys.iter()
+            .map(|key| (key.to_lowercase(), key.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn ranked_by_count_then_label() {
+        assert_eq!(
+            ranked(entries(&["b", "a", "B", "c", "a", "b"]).into_iter()),
+            vec![
+                ("b".to_string(), 3),
+                ("a".to_string(), 2),
+                ("c".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn listed_takes_the_top() {
+        let ranked = ranked(entries(&["a", "a", "b", "c"]).into_iter());
+
+        assert_eq!(listed(&ranked, 2).unwrap(), "1. a (2)\n2. b (1)");
+        assert_eq!(listed(&[], 2), None);
+    }
+}
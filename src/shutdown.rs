@@ -0,0 +1,119 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use log::*;
+use tokio::sync::Notify;
+
+/// Tracks in-flight work so a shutdown can stop taking new messages and wait for the
+/// conversions that are already running.
+#[derive(Debug, Default)]
+pub struct Shutdown {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// Held for the duration of a conversion, releases its slot when dropped.
+pub struct InFlight(Arc<Shutdown>);
+
+impl Shutdown {
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Registers a new unit of work, or returns `None` when we are shutting down.
+    pub fn begin(self: &Arc<Self>) -> Option<InFlight> {
+        // Count ourselves first and check afterwards, a drain that already saw zero has set the
+        // flag by then. Dropping the slot again wakes it up if we were the last one.
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let in_flight = InFlight(self.clone());
+
+        if self.is_draining() {
+            return None;
+        }
+
+        Some(in_flight)
+    }
+
+    /// Stops accepting new work and waits up to `timeout` for the remaining work to finish.
+    /// Returns false if we gave up waiting.
+    pub async fn drain(&self, timeout: Duration) -> bool {
+        self.draining.store(true, Ordering::SeqCst);
+
+        let wait = async {
+            loop {
+                let idle = self.idle.notified();
+                if self.in_flight.load(Ordering::SeqCst) == 0 {
+                    break;
+                }
+                idle.await;
+            }
+        };
+
+        tokio::time::timeout(timeout, wait).await.is_ok()
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+/// Resolves once we receive ctrl+c, or SIGTERM from docker.
+pub async fn signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let Ok(mut terminate) = signal(SignalKind::terminate()) else {
+            warn!("Unable to listen for SIGTERM, falling back to ctrl+c only");
+            let _ = tokio::signal::ctrl_c().await;
+            return;
+        };
+
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => info!("Received ctrl+c"),
+            _ = terminate.recv() => info!("Received SIGTERM"),
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+        info!("Received ctrl+c");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn no_work_after_drain() {
+        let shutdown: Arc<Shutdown> = Default::default();
+
+        let running = shutdown.begin().unwrap();
+        let drained = tokio::spawn({
+            let shutdown = shutdown.clone();
+            async move { shutdown.drain(Duration::from_secs(5)).await }
+        });
+
+        // Wait for the drain to flip the flag before trying to start more work.
+        while !shutdown.is_draining() {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(shutdown.begin().is_none());
+        drop(running);
+        assert!(drained.await.unwrap());
+        assert_eq!(shutdown.in_flight.load(Ordering::SeqCst), 0);
+    }
+}
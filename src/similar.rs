@@ -0,0 +1,251 @@
+use log::*;
+use reqwest::Method;
+use serde_json::Value;
+use serenity::builder::CreateEmbed;
+use thiserror::Error;
+
+use crate::{
+    api::AppleMusicApi,
+    cache::TtlCache,
+    cider_links::LinkButtons,
+    commands::Hint,
+    conversion::{self, MediaType},
+    tr, util, ValuePath,
+};
+
+const MAX_RECOMMENDATIONS: usize = 8;
+
+#[derive(Error, Debug)]
+pub enum SimilarError {
+    #[error("nothing similar found")]
+    NotFound,
+    #[error("request failed")]
+    RequestError(#[from] reqwest::Error),
+}
+
+impl Hint for SimilarError {
+    fn id(&self) -> &'static str {
+        match self {
+            SimilarError::NotFound => "similar-not-found",
+            SimilarError::RequestError(_) => "similar-request",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Recommendation {
+    pub name: String,
+    pub artist: Option<String>,
+    pub url: String,
+}
+
+impl Recommendation {
+    fn from_resource(resource: &Value) -> Option<Recommendation> {
+        let attribute = |name: &str| {
+            resource
+                .get_value_by_path(&format!("attributes.{name}"))
+                .and_then(|value| value.as_str().map(str::to_string))
+        };
+
+        Some(Recommendation {
+            name: attribute("name")?,
+            artist: attribute("artistName"),
+            url: attribute("url")?,
+        })
+    }
+}
+
+/// The id of the button we put on song, album and artist embeds, see [`recommend`].
+pub fn button_id(media_type: MediaType, storefront: &str, id: &str) -> Option<String> {
+    let kind = match media_type {
+        MediaType::Song => "songs",
+        MediaType::Album => "albums",
+        MediaType::Artist => "artists",
+        _ => return None,
+    };
+
+    Some(format!("similar:{storefront}:{kind}:{id}"))
+}
+
+/// Storefront, kind and id packed into a [`button_id`] after the prefix.
+fn parse_key(key: &str) -> Option<(&str, &str, &str)> {
+    let mut parts = key.split(':');
+    Some((parts.next()?, parts.next()?, parts.next()?))
+}
+
+fn resources(resp: &Value) -> Vec<Recommendation> {
+    match resp.get_value_by_path("data") {
+        Some(Value::Array(resources)) => resources
+            .iter()
+            .filter_map(Recommendation::from_resource)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+async fn view(
+    api: &AppleMusicApi,
+    storefront: &str,
+    kind: &str,
+    id: &str,
+    view: &str,
+) -> Result<Vec<Recommendation>, reqwest::Error> {
+    let resp = api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/{kind}/{id}/view/{view}?limit={MAX_RECOMMENDATIONS}"),
+        )
+        .await?;
+
+    Ok(resources(&resp))
+}
+
+/// Songs don't have anything related themselves, so use their station and whatever is
+/// related to their album and artist.
+async fn song(
+    api: &AppleMusicApi,
+    storefront: &str,
+    id: &str,
+) -> Result<Vec<Recommendation>, reqwest::Error> {
+    // Same request as the conversion, so this comes out of the cache.
+    let song = api
+        .request_endpoint(Method::GET, &conversion::song_endpoint(storefront, id))
+        .await?;
+
+    let relationship = |name: &str| {
+        song.get_value_by_path(&format!("data.0.relationships.{name}.data.0.id"))
+            .and_then(|id| id.as_str().map(str::to_string))
+    };
+
+    let mut recommendations = Vec::new();
+
+    match api
+        .request_endpoint(
+            Method::GET,
+            &format!("v1/catalog/{storefront}/songs/{id}/station"),
+        )
+        .await
+    {
+        Ok(station) => recommendations.extend(resources(&station)),
+        Err(e) => warn!("Unable to request the station of song {id}: {e}"),
+    }
+
+    if let Some(album_id) = relationship("albums") {
+        recommendations.extend(view(api, storefront, "albums", &album_id, "related-albums").await?);
+    }
+
+    if recommendations.len() < MAX_RECOMMENDATIONS {
+        if let Some(artist_id) = relationship("artists") {
+            recommendations
+                .extend(view(api, storefront, "artists", &artist_id, "similar-artists").await?);
+        }
+    }
+
+    Ok(recommendations)
+}
+
+async fn lookup(
+    api: &AppleMusicApi,
+    storefront: &str,
+    kind: &str,
+    id: &str,
+) -> Result<Vec<Recommendation>, SimilarError> {
+    let mut recommendations = match kind {
+        "songs" => song(api, storefront, id).await?,
+        "albums" => view(api, storefront, kind, id, "related-albums").await?,
+        "artists" => view(api, storefront, kind, id, "similar-artists").await?,
+        _ => return Err(SimilarError::NotFound),
+    };
+
+    recommendations.truncate(MAX_RECOMMENDATIONS);
+    Ok(recommendations)
+}
+
+/// Handles the similar button, returns `None` if the custom id isn't ours. Lists are kept in
+/// `cache` since a song takes a few requests to build one.
+pub async fn recommend(
+    custom_id: &str,
+    api: &AppleMusicApi,
+    cache: &TtlCache<Vec<Recommendation>>,
+    locale: &str,
+    links: &LinkButtons,
+) -> Option<Result<CreateEmbed, SimilarError>> {
+    let key = custom_id.strip_prefix("similar:")?;
+    let (storefront, kind, id) = parse_key(key)?;
+
+    let recommendations = match cache.get(key).await {
+        Some(recommendations) => recommendations,
+        None => match lookup(api, storefront, kind, id).await {
+            Ok(recommendations) => {
+                cache.insert(key.to_string(), recommendations.clone()).await;
+                recommendations
+            }
+            Err(err) => return Some(Err(err)),
+        },
+    };
+
+    if recommendations.is_empty() {
+        return Some(Err(SimilarError::NotFound));
+    }
+
+    let description = recommendations
+        .iter()
+        .map(|recommendation| {
+            let link =
+                util::masked_link(&recommendation.name, &links.text_url(&recommendation.url));
+            match &recommendation.artist {
+                Some(artist) => format!("{link} - {artist}"),
+                None => link,
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut embed = CreateEmbed::default();
+    embed
+        .title(tr!(locale, "similar-title"))
+        .description(description);
+
+    Some(Ok(embed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELATED: &str = include_str!("../fixtures/catalog/related-albums.json");
+
+    #[test]
+    fn recommendations_need_a_name_and_url() {
+        let resp: Value = serde_json::from_str(RELATED).unwrap();
+
+        assert_eq!(
+            resources(&resp),
+            vec![
+                Recommendation {
+                    name: "1989 (Deluxe Edition) [Taylor's Version]".to_string(),
+                    artist: Some("Taylor Swift".to_string()),
+                    url: "https://music.apple.com/us/album/1989-deluxe-edition/1440913923"
+                        .to_string(),
+                },
+                // Playlists have a curator instead, they are listed without anyone.
+                Recommendation {
+                    name: "Today's Hits".to_string(),
+                    artist: None,
+                    url: "https://music.apple.com/us/playlist/todays-hits/pl.f4d106fed2bd41149aaacabb233eb5eb"
+                        .to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn button_id_round_trip() {
+        let custom_id = button_id(MediaType::Song, "us", "1528112362").unwrap();
+        let key = custom_id.strip_prefix("similar:").unwrap();
+
+        assert_eq!(parse_key(key), Some(("us", "songs", "1528112362")));
+        assert_eq!(parse_key("us:songs"), None);
+        assert_eq!(button_id(MediaType::Playlist, "us", "pl.123"), None);
+    }
+}
@@ -0,0 +1,479 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use log::*;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use surrealdb::{
+    engine::any::{self, Any},
+    opt::auth::Root,
+    Surreal,
+};
+use thiserror::Error;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{Mutex, MutexGuard},
+};
+
+use crate::{conversion::ConversionRecord, guild::GuildConfig, util::CFG_PATH, Stats};
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("could not access the storage directory")]
+    Io(#[from] std::io::Error),
+    #[error("could not serialize stored data")]
+    Json(#[from] serde_json::Error),
+    #[error("database request failed")]
+    Database(#[from] surrealdb::Error),
+}
+
+/// Switches every process has to follow, changed through `/admin`.
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeFlags {
+    pub maintenance: bool,
+    /// When the caches were last cleared, in unix seconds.
+    pub cache_cleared: i64,
+}
+
+/// Where everything that has to outlive the process lives. Single process deployments can
+/// get away with json files next to the bot, once we run multiple shard processes the
+/// state has to be shared through SurrealDB instead.
+pub enum Storage {
+    Files {
+        root: PathBuf,
+        lock: Mutex<()>,
+        /// conversions.jsonl parsed on first use and kept up to date as we record, so queries
+        /// don't reread the whole history every time. Only this process writes the file.
+        records: Mutex<Option<Vec<ConversionRecord>>>,
+    },
+    Surreal(Surreal<Any>),
+}
+
+impl Storage {
+    /// Connects to SurrealDB when `SURREAL_URL` is set, otherwise falls back to files in `CFG_PATH`.
+    pub async fn connect() -> Result<Storage, StorageError> {
+        let Ok(url) = std::env::var("SURREAL_URL") else {
+            info!("Using file storage in {}", CFG_PATH.display());
+            return Ok(Storage::Files {
+                root: CFG_PATH.clone(),
+                lock: Mutex::new(()),
+                records: Mutex::new(None),
+            });
+        };
+
+        info!("Using SurrealDB storage at {url}");
+
+        let db = any::connect(url).await?;
+
+        if let (Ok(username), Ok(password)) =
+            (std::env::var("SURREAL_USER"), std::env::var("SURREAL_PASS"))
+        {
+            db.signin(Root {
+                username: &username,
+                password: &password,
+            })
+            .await?;
+        }
+
+        db.use_ns(std::env::var("SURREAL_NS").unwrap_or("cidar".to_string()))
+            .use_db(std::env::var("SURREAL_DB").unwrap_or("cidar".to_string()))
+            .await?;
+
+        Ok(Storage::Surreal(db))
+    }
+
+    /// Waits for a file write that is still going, so exiting doesn't cut it off. There is
+    /// nothing else to flush: every counter, record and setting is written as it changes, and
+    /// SurrealDB has acknowledged its writes by the time they return.
+    pub async fn flush(&self) {
+        if let Storage::Files { lock, .. } = self {
+            let _guard = lock.lock().await;
+        }
+    }
+
+    pub fn is_shared(&self) -> bool {
+        matches!(self, Storage::Surreal(_))
+    }
+
+    pub async fn stats(&self) -> Result<Stats, StorageError> {
+        match self {
+            Storage::Files { root, .. } => read_file(root, "stats.json").await,
+            Storage::Surreal(db) => Ok(db
+                .select::<Option<Stats>>(("stats", "global"))
+                .await?
+                .unwrap_or_default()),
+        }
+    }
+
+    pub async fn increment_conversion(&self) -> Result<Stats, StorageError> {
+        match self {
+            Storage::Files { root, lock, .. } => {
+                let _guard = lock.lock().await;
+
+                let mut stats: Stats = read_file(root, "stats.json").await?;
+
+                info!(
+                    "incrementing conversions; {} -> {}",
+                    stats.total_conversions,
+                    stats.total_conversions + 1
+                );
+                stats.total_conversions += 1;
+
+                write_file(root, "stats.json", &stats).await?;
+                Ok(stats)
+            }
+            Storage::Surreal(db) => {
+                // Let the database do the increment so concurrent shard processes don't race.
+                let stats: Option<Stats> = db
+                    .query(
+                        "UPDATE stats:global SET total_conversions = (total_conversions OR 0) + 1",
+                    )
+                    .await?
+                    .take(0)?;
+
+                Ok(stats.unwrap_or_default())
+            }
+        }
+    }
+
+    pub async fn record_conversion(&self, record: &ConversionRecord) -> Result<(), StorageError> {
+        match self {
+            Storage::Files {
+                root,
+                lock,
+                records,
+            } => {
+                let _guard = lock.lock().await;
+
+                // One record per line, so recording never has to rewrite the whole history.
+                let mut line = serde_json::to_vec(record)?;
+                line.push(b'\n');
+
+                tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(root.join("conversions.jsonl"))
+                    .await?
+                    .write_all(&line)
+                    .await?;
+
+                if let Some(records) = records.lock().await.as_mut() {
+                    records.push(record.clone());
+                }
+
+                Ok(())
+            }
+            Storage::Surreal(db) => {
+                db.create::<Vec<ConversionRecord>>("conversion")
+                    .content(record)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Conversions made since `since` (unix seconds), limited to a guild if one is given.
+    pub async fn conversions(
+        &self,
+        guild_id: Option<u64>,
+        since: i64,
+    ) -> Result<Vec<ConversionRecord>, StorageError> {
+        match self {
+            Storage::Files {
+                root,
+                lock,
+                records,
+            } => {
+                let records = loaded(root, lock, records).await?;
+
+                Ok(records
+                    .iter()
+                    .flatten()
+                    .filter(|record| record.timestamp >= since)
+                    .filter(|record| guild_id.is_none() || record.guild_id == guild_id)
+                    .cloned()
+                    .collect())
+            }
+            Storage::Surreal(db) => Ok(db
+                .query(
+                    "SELECT * FROM conversion WHERE timestamp >= $since \
+                     AND ($guild = NONE OR guild_id = $guild)",
+                )
+                .bind(("since", since))
+                .bind(("guild", guild_id))
+                .await?
+                .take(0)?),
+        }
+    }
+
+    /// How many conversions were made since `since`, without loading the records themselves.
+    pub async fn count_conversions(
+        &self,
+        guild_id: Option<u64>,
+        since: i64,
+    ) -> Result<u64, StorageError> {
+        match self {
+            Storage::Files {
+                root,
+                lock,
+                records,
+            } => {
+                let records = loaded(root, lock, records).await?;
+
+                Ok(records
+                    .iter()
+                    .flatten()
+                    .filter(|record| record.timestamp >= since)
+                    .filter(|record| guild_id.is_none() || record.guild_id == guild_id)
+                    .count() as u64)
+            }
+            Storage::Surreal(db) => {
+                let count: Option<Count> = db
+                    .query(
+                        "SELECT count() FROM conversion WHERE timestamp >= $since \
+                         AND ($guild = NONE OR guild_id = $guild) GROUP ALL",
+                    )
+                    .bind(("since", since))
+                    .bind(("guild", guild_id))
+                    .await?
+                    .take(0)?;
+
+                Ok(count.map(|count| count.count).unwrap_or_default())
+            }
+        }
+    }
+
+    pub async fn guild_config(&self, guild_id: u64) -> Result<GuildConfig, StorageError> {
+        match self {
+            Storage::Files { root, .. } => {
+                let mut guilds: HashMap<u64, GuildConfig> = read_file(root, "guilds.json").await?;
+                Ok(guilds.remove(&guild_id).unwrap_or_default())
+            }
+            Storage::Surreal(db) => Ok(db
+                .select::<Option<StoredGuild>>(("guild", guild_id.to_string()))
+                .await?
+                .map(|stored| stored.config)
+                .unwrap_or_default()),
+        }
+    }
+
+    pub async fn set_guild_config(
+        &self,
+        guild_id: u64,
+        config: &GuildConfig,
+    ) -> Result<(), StorageError> {
+        match self {
+            Storage::Files { root, lock, .. } => {
+                let _guard = lock.lock().await;
+
+                let mut guilds: HashMap<u64, GuildConfig> = read_file(root, "guilds.json").await?;
+                guilds.insert(guild_id, config.clone());
+                write_file(root, "guilds.json", &guilds).await
+            }
+            Storage::Surreal(db) => {
+                db.update::<Option<StoredGuild>>(("guild", guild_id.to_string()))
+                    .content(StoredGuild {
+                        guild_id,
+                        config: config.clone(),
+                    })
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Every guild that changed at least one setting.
+    pub async fn guild_configs(&self) -> Result<HashMap<u64, GuildConfig>, StorageError> {
+        match self {
+            Storage::Files { root, .. } => read_file(root, "guilds.json").await,
+            Storage::Surreal(db) => Ok(db
+                .select::<Vec<StoredGuild>>("guild")
+                .await?
+                .into_iter()
+                .map(|stored| (stored.guild_id, stored.config))
+                .collect()),
+        }
+    }
+
+    pub async fn runtime_flags(&self) -> Result<RuntimeFlags, StorageError> {
+        match self {
+            Storage::Files { root, .. } => read_file(root, "runtime.json").await,
+            Storage::Surreal(db) => Ok(db
+                .select::<Option<RuntimeFlags>>(("meta", "runtime"))
+                .await?
+                .unwrap_or_default()),
+        }
+    }
+
+    pub async fn set_runtime_flags(&self, flags: &RuntimeFlags) -> Result<(), StorageError> {
+        match self {
+            Storage::Files { root, lock, .. } => {
+                let _guard = lock.lock().await;
+                write_file(root, "runtime.json", flags).await
+            }
+            Storage::Surreal(db) => {
+                db.update::<Option<RuntimeFlags>>(("meta", "runtime"))
+                    .content(flags)
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// The command definitions we last registered with discord, see [`crate::commands::sync`].
+    pub async fn command_definitions(&self) -> Result<Option<String>, StorageError> {
+        match self {
+            Storage::Files { root, .. } => read_file(root, "commands.json").await,
+            Storage::Surreal(db) => Ok(db
+                .select::<Option<StoredCommands>>(("meta", "commands"))
+                .await?
+                .map(|stored| stored.definitions)),
+        }
+    }
+
+    pub async fn set_command_definitions(&self, definitions: &str) -> Result<(), StorageError> {
+        match self {
+            Storage::Files { root, lock, .. } => {
+                let _guard = lock.lock().await;
+                write_file(root, "commands.json", &definitions).await
+            }
+            Storage::Surreal(db) => {
+                db.update::<Option<StoredCommands>>(("meta", "commands"))
+                    .content(StoredCommands {
+                        definitions: definitions.to_string(),
+                    })
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Guild configs are keyed by their id in SurrealDB, but we still need it back when listing them.
+#[derive(Serialize, Deserialize)]
+struct StoredGuild {
+    guild_id: u64,
+    config: GuildConfig,
+}
+
+#[derive(Deserialize)]
+struct Count {
+    count: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredCommands {
+    definitions: String,
+}
+
+/// The parsed conversion records, read from conversions.jsonl the first time they are needed.
+async fn loaded<'a>(
+    root: &Path,
+    lock: &Mutex<()>,
+    records: &'a Mutex<Option<Vec<ConversionRecord>>>,
+) -> Result<MutexGuard<'a, Option<Vec<ConversionRecord>>>, StorageError> {
+    // Same order as recording takes them, the file lock first.
+    let _guard = lock.lock().await;
+    let mut records = records.lock().await;
+
+    if records.is_none() {
+        let parsed = match tokio::fs::read_to_string(root.join("conversions.jsonl")).await {
+            Ok(buffer) => buffer
+                .lines()
+                .filter_map(|line| serde_json::from_str::<ConversionRecord>(line).ok())
+                .collect(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(e.into()),
+        };
+        *records = Some(parsed);
+    }
+
+    Ok(records)
+}
+
+async fn read_file<T: DeserializeOwned + Default>(
+    root: &Path,
+    name: &str,
+) -> Result<T, StorageError> {
+    let buffer = match tokio::fs::read(root.join(name)).await {
+        Ok(buffer) => buffer,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(T::default()),
+        Err(e) => return Err(e.into()),
+    };
+
+    // A corrupt file should not take the bot down, start over like we always have.
+    Ok(serde_json::from_slice(&buffer).unwrap_or_else(|e| {
+        warn!("Unable to read {name}, starting from scratch: {e}");
+        T::default()
+    }))
+}
+
+async fn write_file<T: Serialize>(root: &Path, name: &str, value: &T) -> Result<(), StorageError> {
+    // Write next to the real file and swap it in, so a crash never leaves half a file behind.
+    let temp = root.join(format!("{name}.tmp"));
+    tokio::fs::write(&temp, serde_json::to_vec_pretty(value)?).await?;
+    tokio::fs::rename(temp, root.join(name)).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conversion::{MediaType, Platform};
+
+    fn files(root: &Path) -> Storage {
+        Storage::Files {
+            root: root.to_path_buf(),
+            lock: Mutex::new(()),
+            records: Mutex::new(None),
+        }
+    }
+
+    fn record(timestamp: i64, guild_id: u64) -> ConversionRecord {
+        ConversionRecord {
+            timestamp,
+            guild_id: Some(guild_id),
+            author_id: 1,
+            author_name: "someone".to_string(),
+            channel_id: 2,
+            message_id: 3,
+            platform: Platform::Spotify,
+            media_type: MediaType::Song,
+            catalog_id: None,
+            title: "cardigan".to_string(),
+            artist: None,
+            artist_id: None,
+            album: None,
+            album_id: None,
+            url: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn records_stay_in_step_with_the_file() {
+        let root = std::env::temp_dir().join(format!("cidar-storage-{}", std::process::id()));
+        std::fs::create_dir_all(&root).unwrap();
+        let _ = std::fs::remove_file(root.join("conversions.jsonl"));
+
+        let storage = files(&root);
+        storage.record_conversion(&record(10, 1)).await.unwrap();
+        assert_eq!(storage.conversions(None, 0).await.unwrap().len(), 1);
+
+        // Recorded after the file was loaded, has to show up without reading it again.
+        storage.record_conversion(&record(20, 2)).await.unwrap();
+        assert_eq!(storage.count_conversions(None, 0).await.unwrap(), 2);
+        assert_eq!(storage.count_conversions(Some(2), 0).await.unwrap(), 1);
+        assert_eq!(
+            storage.conversions(None, 15).await.unwrap()[0].timestamp,
+            20
+        );
+
+        // A fresh process reads the same from the file.
+        assert_eq!(files(&root).count_conversions(None, 0).await.unwrap(), 2);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -1,69 +1,436 @@
-use std::time::Duration;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 use log::*;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
-use serenity::model::gateway::Activity;
+use serenity::model::id::{ChannelId, GuildId};
 use serenity::model::user::OnlineStatus;
-use tokio::fs::OpenOptions;
+use serenity::model::Timestamp;
+
+use tokio::sync::RwLock;
 
 use crate::{
-    util::{create_conversion_counter, read_json, CFG_PATH},
-    Stats, TokenLock,
+    api::AppleMusicApi,
+    cider_links::{self, Action, LinkButtons},
+    commands::follow,
+    config::{ActivityKind, Config},
+    conversion::MediaType,
+    guild::{Follow, GuildConfig},
+    health::{Health, Upstream},
+    i18n,
+    ranking::{key, listed, ranked},
+    storage::{Storage, StorageError},
+    tr,
+    util::{self, DAY},
+    TokenLock, ValuePath,
 };
 
+const WEEK: i64 = DAY * 7;
+const DIGEST_ENTRIES: usize = 10;
+/// How long an `/admin` switch takes to reach the other processes.
+pub const RUNTIME_SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Debug, Serialize, Deserialize)]
 struct TokenBody {
     token: String,
 }
 
-pub async fn token_updater(token: TokenLock) {
+/// Fetches a fresh developer token, returns false and keeps the previous one if that fails.
+pub async fn refresh_token(client: &reqwest::Client, token: &TokenLock, health: &Health) -> bool {
+    let Ok(response) = client
+        .get("https://api.cider.sh/v1")
+        .header("User-Agent", "Cider")
+        .header("Referer", "tauri.localhost")
+        .send()
+        .await
+    else {
+        error!("Failed to get new token, keeping previous");
+        return false;
+    };
+
+    let Ok(serialized) = response.json::<TokenBody>().await else {
+        error!("Failed to get new token, keeping previous");
+        return false;
+    };
+
+    *token.write().await = Some(serialized.token);
+    health.upstream_ok(Upstream::CiderApi).await;
+    health.token_refreshed().await;
+
+    true
+}
+
+pub async fn token_updater(token: TokenLock, health: Arc<Health>) {
     let client = reqwest::Client::new();
     loop {
-        let Ok(response) = client
-            .get("https://api.cider.sh/v1")
-            .header("User-Agent", "Cider")
-            .header("Referer", "tauri.localhost")
-            .send()
-            .await
-        else {
-            error!("Failed to get new token, keeping previous");
-            return;
-        };
+        refresh_token(&client, &token, &health).await;
 
-        let Ok(serialized) = response.json::<TokenBody>().await else {
-            error!("Failed to get new token, keeping previous");
-            return;
-        };
+        tokio::time::sleep(Duration::from_secs(60 * 30)).await; // Sleep for 30 minutes
+    }
+}
 
-        *token.write().await = Some(serialized.token);
+/// Fills in the placeholders of a presence template.
+async fn render_presence(
+    template: &str,
+    ctx: &serenity::prelude::Context,
+    storage: &Storage,
+) -> Result<String, StorageError> {
+    let mut text = template.replace("{version}", env!("CARGO_PKG_VERSION"));
 
-        tokio::time::sleep(Duration::from_secs(60 * 30)).await; // Sleep for 30 minutes
+    if text.contains("{conversions}") {
+        let stats = storage.stats().await?;
+        text = text.replace("{conversions}", &stats.total_conversions.to_string());
+    }
+
+    if text.contains("{guilds}") {
+        text = text.replace("{guilds}", &ctx.cache.guild_count().to_string());
+    }
+
+    if text.contains("{top_song}") {
+        let since = Timestamp::now().unix_timestamp() - DAY;
+        let records = storage.conversions(None, since).await?;
+
+        let top = ranked(
+            records
+                .iter()
+                .filter(|record| record.media_type == MediaType::Song)
+                .map(|record| (key(&record.catalog_id, &record.title), record.title.clone())),
+        );
+
+        let top = top
+            .first()
+            .map(|(title, _)| title.as_str())
+            .unwrap_or("nothing yet");
+        text = text.replace("{top_song}", top);
+    }
+
+    Ok(text)
+}
+
+pub async fn status_updater(
+    ctx: serenity::prelude::Context,
+    storage: Arc<Storage>,
+    config: Arc<RwLock<Config>>,
+) {
+    // What we last sent to discord, so we only update the presence when it actually changes.
+    let mut current: Option<(ActivityKind, String, OnlineStatus)> = None;
+
+    for index in 0.. {
+        // Read the config every time around, so a reload applies without restarting.
+        let presence = config.read().await.presence.clone();
+        let template = &presence.templates[index % presence.templates.len()];
+
+        match render_presence(&template.text, &ctx, &storage).await {
+            Ok(text) => {
+                let next = (template.kind, text, presence.status);
+
+                if current.as_ref() != Some(&next) {
+                    ctx.set_presence(Some(next.0.activity(&next.1)), next.2)
+                        .await;
+                    current = Some(next);
+                }
+            }
+            Err(e) => warn!("Unable to build presence: {e}"),
+        }
+
+        tokio::time::sleep(presence.interval).await;
     }
 }
 
-pub async fn status_updater(ctx: serenity::prelude::Context) {
-    let status = OnlineStatus::DoNotDisturb;
+async fn post_digest(
+    ctx: &serenity::prelude::Context,
+    storage: &Storage,
+    guild_id: u64,
+    channel: u64,
+    since: i64,
+    locale: &str,
+    links: &LinkButtons,
+) -> Result<(), StorageError> {
+    let records = storage.conversions(Some(guild_id), since).await?;
+
+    // Nothing to recap, don't bother the channel.
+    if records.is_empty() {
+        return Ok(());
+    }
+
+    let tracks = ranked(
+        records
+            .iter()
+            .filter(|record| record.media_type == MediaType::Song)
+            .filter_map(|record| {
+                let url = record.url.as_ref()?;
+                let artist = match &record.artist {
+                    Some(artist) => artist.clone(),
+                    None => tr!(locale, "unknown-artist"),
+                };
+                let title = tr!(
+                    locale,
+                    "title-by",
+                    title = util::masked_link(&record.title, url),
+                    artist = artist
+                );
+
+                // The title already opens apple music, add Cider the way the guild's buttons are.
+                let entry = match links.text_action() {
+                    Some(action) => {
+                        let label = match action {
+                            Action::Play => "play-in-cider",
+                            Action::View => "view-in-cider",
+                        };
+                        format!(
+                            "{title} • {}",
+                            util::masked_link(&tr!(locale, label), &cider_links::web(url, action))
+                        )
+                    }
+                    None => title,
+                };
+
+                Some((key(&record.catalog_id, &record.title), entry))
+            }),
+    );
+
+    let top = |ranked: &[(String, usize)]| {
+        listed(ranked, DIGEST_ENTRIES).unwrap_or_else(|| tr!(locale, "nothing-shared"))
+    };
+
+    let sharers = ranked(
+        records
+            .iter()
+            .filter(|record| record.author_id != 0)
+            .map(|record| (record.author_id.to_string(), record.author_name.clone())),
+    );
+
+    if let Err(why) = ChannelId(channel)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| {
+                e.title(tr!(locale, "digest-title"))
+                    .description(top(&tracks))
+                    .field(tr!(locale, "digest-top-sharers"), top(&sharers), false)
+                    .footer(|f| f.text(tr!(locale, "digest-footer", count = records.len())))
+                    .timestamp(Timestamp::now())
+            })
+        })
+        .await
+    {
+        warn!("Unable to post the weekly digest for guild {guild_id}: {why}");
+    }
+
+    Ok(())
+}
+
+pub async fn digest_poster(ctx: serenity::prelude::Context, storage: Arc<Storage>) {
     loop {
-        let mut file = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .create(true)
-            .open(CFG_PATH.join("stats.json"))
+        let configs = match storage.guild_configs().await {
+            Ok(configs) => configs,
+            Err(e) => {
+                warn!("Unable to read guild configs: {e}");
+                HashMap::new()
+            }
+        };
+
+        // Only the process running the guild's shard posts its digest.
+        let ours = ctx.cache.guilds();
+        let now = Timestamp::now().unix_timestamp();
+
+        for (guild_id, config) in configs {
+            let Some(channel) = config.digest_channel else {
+                continue;
+            };
+
+            if now - config.last_digest < WEEK || !ours.contains(&GuildId(guild_id)) {
+                continue;
+            }
+
+            let locale = i18n::guild_locale(&ctx, guild_id, &config);
+
+            if let Err(e) = post_digest(
+                &ctx,
+                &storage,
+                guild_id,
+                channel,
+                config.last_digest,
+                &locale,
+                &config.cider_links,
+            )
             .await
-            .expect("Unable to open stats.json");
+            {
+                warn!("Unable to build the weekly digest for guild {guild_id}: {e}");
+            }
+
+            // Mark it as posted even if sending failed, a missing channel shouldn't retry every hour.
+            // Re-read the config first so we don't undo a /config change made in the meantime.
+            let result = match storage.guild_config(guild_id).await {
+                Ok(mut latest) => {
+                    latest.last_digest = now;
+                    storage.set_guild_config(guild_id, &latest).await
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                warn!("Unable to save the digest schedule for guild {guild_id}: {e}");
+            }
+        }
+
+        tokio::time::sleep(Duration::from_secs(60 * 60)).await; // Check every hour
+    }
+}
+
+/// Picks up the `/admin` switches made in any process, maintenance mode and cache clears
+/// have to reach every shard and not just the one that ran the command.
+pub async fn runtime_sync(
+    storage: Arc<Storage>,
+    api: Arc<AppleMusicApi>,
+    maintenance: Arc<AtomicBool>,
+) {
+    let mut cache_cleared = None;
+
+    loop {
+        match storage.runtime_flags().await {
+            Ok(flags) => {
+                maintenance.store(flags.maintenance, Ordering::SeqCst);
+
+                // Clears from before we started don't concern our fresh cache.
+                if cache_cleared.is_some_and(|at| flags.cache_cleared > at) {
+                    let cleared = api.cache.clear().await;
+                    info!("Cleared {cleared} cached catalog responses for an admin");
+                }
+                cache_cleared = Some(flags.cache_cleared);
+            }
+            Err(e) => warn!("Unable to read the runtime flags: {e}"),
+        }
+
+        tokio::time::sleep(RUNTIME_SYNC_INTERVAL).await;
+    }
+}
+
+/// Announces a followed artist's new release, returns whether it was posted.
+async fn post_release(
+    ctx: &serenity::prelude::Context,
+    guild_id: u64,
+    follow: &Follow,
+    release: &Value,
+    config: &GuildConfig,
+    locale: &str,
+) -> bool {
+    let Some(information) = follow::announcement(follow, release, locale) else {
+        warn!("Unable to read the latest release of {}", follow.artist_id);
+        return false;
+    };
+
+    if let Err(why) = ChannelId(follow.channel)
+        .send_message(&ctx.http, |m| {
+            m.embed(|e| information.embed(e, locale, config))
+                .components(|c| information.buttons(c, locale, &config.cider_links))
+        })
+        .await
+    {
+        warn!("Unable to announce a release for guild {guild_id}: {why}");
+    }
+
+    // Don't retry a channel we can't post in every round, same as the digest.
+    true
+}
 
-        let read_stats: Stats = match read_json(&mut file).await {
-            Ok(s) => s,
-            Err(_) => create_conversion_counter().await,
+pub async fn release_poller(
+    ctx: serenity::prelude::Context,
+    storage: Arc<Storage>,
+    api: Arc<AppleMusicApi>,
+) {
+    loop {
+        let configs = match storage.guild_configs().await {
+            Ok(configs) => configs,
+            Err(e) => {
+                warn!("Unable to read guild configs: {e}");
+                HashMap::new()
+            }
         };
 
-        let activity = Activity::listening(format!(
-            "Cider | {} songs converted",
-            read_stats.total_conversions
-        ));
+        // Only the process running the guild's shard posts its releases.
+        let ours = ctx.cache.guilds();
+
+        // Plenty of guilds follow the same artists, only ask apple once per round.
+        let mut latest: HashMap<(String, String), Option<Value>> = HashMap::new();
+
+        for (guild_id, config) in configs {
+            if config.follows.is_empty() || !ours.contains(&GuildId(guild_id)) {
+                continue;
+            }
+
+            let locale = i18n::guild_locale(&ctx, guild_id, &config);
+            let mut announced = Vec::new();
+
+            for follow in &config.follows {
+                let key = (follow.storefront.clone(), follow.artist_id.clone());
+
+                if !latest.contains_key(&key) {
+                    let release =
+                        match follow::latest_release(&api, &follow.storefront, &follow.artist_id)
+                            .await
+                        {
+                            Ok(release) => release,
+                            Err(e) => {
+                                warn!("Unable to request the latest release of {}: {e}", key.1);
+                                None
+                            }
+                        };
+                    latest.insert(key.clone(), release);
+                }
+
+                let Some(release) = &latest[&key] else {
+                    continue;
+                };
+
+                let Some(release_id) = release
+                    .get_value_by_path("id")
+                    .and_then(|id| id.as_str().map(str::to_string))
+                else {
+                    continue;
+                };
+
+                if follow.last_release.as_ref() == Some(&release_id) {
+                    continue;
+                }
+
+                if post_release(&ctx, guild_id, follow, release, &config, &locale).await {
+                    announced.push((follow.artist_id.clone(), follow.channel, release_id));
+                }
+            }
+
+            if announced.is_empty() {
+                continue;
+            }
+
+            // Re-read the config first so we don't undo a /follow change made in the meantime.
+            let result = match storage.guild_config(guild_id).await {
+                Ok(mut current) => {
+                    for follow in &mut current.follows {
+                        if let Some((.., release_id)) =
+                            announced.iter().find(|(artist_id, channel, _)| {
+                                *artist_id == follow.artist_id && *channel == follow.channel
+                            })
+                        {
+                            follow.last_release = Some(release_id.clone());
+                        }
+                    }
+                    storage.set_guild_config(guild_id, &current).await
+                }
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = result {
+                warn!("Unable to save the announced releases for guild {guild_id}: {e}");
+            }
+        }
 
-        ctx.set_presence(Some(activity.clone()), status).await;
-        tokio::time::sleep(Duration::from_secs(10)).await;
+        tokio::time::sleep(Duration::from_secs(60 * 60 * 3)).await; // Check every 3 hours
     }
 }
@@ -1,14 +1,10 @@
 use once_cell::sync::Lazy;
+use serenity::model::Timestamp;
 use std::{path::PathBuf, str::FromStr, time::Duration};
-use tokio::{
-    fs::{File, OpenOptions},
-    io::{AsyncReadExt, AsyncWriteExt},
-};
 
-use log::*;
-use serde::{Deserialize, Serialize};
+use crate::tr;
 
-use crate::Stats;
+pub const DAY: i64 = 60 * 60 * 24;
 
 pub fn milli_to_hhmmss(duration: &Duration) -> String {
     let millis = duration.as_millis();
@@ -17,14 +13,152 @@ pub fn milli_to_hhmmss(duration: &Duration) -> String {
     let ss = seconds % 60;
     let mm = (seconds / 60) % 60;
     let hh = (seconds / (60 * 60)) % 24;
+    let dd = seconds / (60 * 60 * 24);
 
-    if hh == 0 && mm != 0 {
-        format!("{:02}:{:02}", mm, ss)
-    } else if hh == 0 && mm == 0 {
-        format!("{:02}", ss)
-    } else {
+    if dd != 0 {
+        format!("{}:{:02}:{:02}:{:02}", dd, hh, mm, ss)
+    } else if hh != 0 {
         format!("{}:{:02}:{:02}", hh, mm, ss)
+    } else {
+        format!("{}:{:02}", mm, ss)
+    }
+}
+
+/// Durations the way people say them, e.g. "1 hr 12 min". Only the two largest units are
+/// shown, nobody cares about the seconds of a three hour album.
+pub fn human_duration(duration: &Duration, locale: &str) -> String {
+    let seconds = duration.as_secs();
+
+    let units = [
+        ("duration-days", seconds / (60 * 60 * 24)),
+        ("duration-hours", (seconds / (60 * 60)) % 24),
+        ("duration-minutes", (seconds / 60) % 60),
+        ("duration-seconds", seconds % 60),
+    ];
+
+    let parts = units
+        .iter()
+        .skip_while(|(_, count)| *count == 0)
+        .take(2)
+        .filter(|(_, count)| *count != 0)
+        .map(|(id, count)| tr!(locale, id, count = *count))
+        .collect::<Vec<_>>();
+
+    if parts.is_empty() {
+        return tr!(locale, "duration-seconds", count = 0);
+    }
+
+    parts.join(" ")
+}
+
+/// Turns an Apple Music release date into timestamp markup, so discord shows it in each
+/// reader's own locale. Anything that isn't a full date (some old releases only have a
+/// year) is passed through as is.
+pub fn discord_date(date: &str) -> String {
+    match Timestamp::parse(&format!("{date}T00:00:00Z")) {
+        Ok(timestamp) => format!("<t:{}:D>", timestamp.unix_timestamp()),
+        Err(_) => date.to_string(),
+    }
+}
+
+/// Works out the most common colour of an image, for artwork apple doesn't give a colour for.
+#[cfg(feature = "dominant-colour")]
+pub async fn dominant_colour(
+    client: &reqwest::Client,
+    url: &str,
+) -> Option<serenity::utils::Colour> {
+    let bytes = client.get(url).send().await.ok()?.bytes().await.ok()?;
+
+    // Decoding is cpu bound, keep it off the async workers.
+    tokio::task::spawn_blocking(move || {
+        let image = image::load_from_memory(&bytes)
+            .ok()?
+            .thumbnail(32, 32)
+            .to_rgb8();
+
+        // Bucket similar colours together, then average the biggest bucket.
+        let mut buckets: std::collections::HashMap<[u8; 3], [u32; 4]> = Default::default();
+        for pixel in image.pixels() {
+            let [r, g, b] = pixel.0;
+            let bucket = buckets.entry([r >> 5, g >> 5, b >> 5]).or_default();
+            bucket[0] += r as u32;
+            bucket[1] += g as u32;
+            bucket[2] += b as u32;
+            bucket[3] += 1;
+        }
+
+        let [r, g, b, count] = buckets.into_values().max_by_key(|bucket| bucket[3])?;
+        Some(serenity::utils::Colour::from_rgb(
+            (r / count) as u8,
+            (g / count) as u8,
+            (b / count) as u8,
+        ))
+    })
+    .await
+    .ok()?
+}
+
+/// Shortens catalog text for an embed, dropping the markup apple sometimes puts in it.
+pub fn snippet(text: &str, max: usize) -> String {
+    let mut plain = String::new();
+    let mut in_tag = false;
+
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => plain.push(c),
+            _ => {}
+        }
+    }
+
+    let plain = plain.trim();
+    if plain.chars().count() <= max {
+        return plain.to_string();
+    }
+
+    // Cut at a word so we don't end halfway through one.
+    let cut = plain.chars().take(max).collect::<String>();
+    let cut = cut
+        .rsplit_once(' ')
+        .map_or(cut.as_str(), |(start, _)| start);
+    format!(
+        "{}…",
+        cut.trim_end_matches(|c: char| c.is_ascii_punctuation())
+    )
+}
+
+/// A markdown link, with the brackets in names like "Song (Live) [Remastered]" escaped so they
+/// don't end the link early.
+pub fn masked_link(text: &str, url: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '[' | ']' | '(' | ')') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    format!("[{escaped}]({url})")
+}
+
+/// Escapes what discord would read as markdown, so names like "*NSYNC" or "Song_Title" show
+/// as written inside bold text.
+pub fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '*' | '_' | '~' | '`' | '|' | '[' | ']') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
     }
+
+    escaped
+}
+
+/// Storefronts are two letter country codes, no point asking apple about anything else.
+pub fn is_storefront(storefront: &str) -> bool {
+    storefront.len() == 2 && storefront.chars().all(|c| c.is_ascii_alphabetic())
 }
 
 pub fn wh(url: &str, w: u32, h: u32) -> String {
@@ -44,76 +178,70 @@ pub static CFG_PATH: Lazy<PathBuf> = Lazy::new(|| {
     .expect("Not a valid path config")
 });
 
-pub async fn increment_conversion() -> Result<(), ()> {
-    let mut opts = OpenOptions::new();
-    opts.read(true);
-    opts.write(true);
-    opts.create(true);
-
-    let mut file = opts
-        .clone()
-        .open(CFG_PATH.join("stats.json"))
-        .await
-        .expect("Unable to open stats.json");
-
-    let mut read_stats: Stats = match read_json(&mut file).await {
-        Ok(s) => s,
-        Err(_) => create_conversion_counter().await,
-    };
-
-    info!(
-        "incrementing conversions; {} -> {}",
-        read_stats.total_conversions,
-        read_stats.total_conversions + 1
-    );
-    read_stats.total_conversions += 1;
-
-    opts.truncate(true);
-    let mut file = opts
-        .clone()
-        .open(CFG_PATH.join("stats.json"))
-        .await
-        .expect("Unable to open stats.json");
-
-    write_json(&read_stats, &mut file).await;
-
-    Ok(())
+pub fn split_authors(authors: &str) -> String {
+    authors.split(':').collect::<Vec<&str>>().join(", ")
 }
 
-pub async fn create_conversion_counter() -> Stats {
-    let mut file = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .create(true)
-        .truncate(true)
-        .open(CFG_PATH.join("stats.json"))
-        .await
-        .expect("Unable to open stats.json");
-
-    let stats = Stats::default();
-    if let Err(e) = file
-        .write_all(serde_json::to_string_pretty(&stats).unwrap().as_bytes())
-        .await
-    {
-        warn!("Unable to write stats.json: {}", e.to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clock_durations() {
+        assert_eq!(milli_to_hhmmss(&Duration::from_secs(42)), "0:42");
+        assert_eq!(milli_to_hhmmss(&Duration::from_secs(3 * 60 + 5)), "3:05");
+        assert_eq!(
+            milli_to_hhmmss(&Duration::from_secs(60 * 60 + 12 * 60)),
+            "1:12:00"
+        );
+        // Hours used to wrap around after a day.
+        assert_eq!(
+            milli_to_hhmmss(&Duration::from_secs(DAY as u64 + 2 * 60 * 60 + 3)),
+            "1:02:00:03"
+        );
     }
 
-    stats
-}
-
-pub fn split_authors(authors: &str) -> String {
-    authors.split(':').collect::<Vec<&str>>().join(", ")
-}
+    #[test]
+    fn human_durations() {
+        assert_eq!(human_duration(&Duration::from_secs(42), "en-US"), "42 sec");
+        assert_eq!(human_duration(&Duration::ZERO, "en-US"), "0 sec");
+        assert_eq!(
+            human_duration(&Duration::from_secs(60 * 60 + 12 * 60 + 30), "en-US"),
+            "1 hr 12 min"
+        );
+        assert_eq!(
+            human_duration(&Duration::from_secs(DAY as u64 + 30 * 60), "en-US"),
+            "1 day"
+        );
+        assert_eq!(
+            human_duration(&Duration::from_secs(2 * DAY as u64 + 60 * 60), "en-US"),
+            "2 days 1 hr"
+        );
+    }
 
-pub async fn write_json<T: Serialize + for<'a> Deserialize<'a>>(json: &T, file: &mut File) {
-    let _ = file.write(&serde_json::to_vec_pretty(json).unwrap()).await;
-}
+    #[test]
+    fn discord_dates() {
+        assert_eq!(discord_date("2023-07-14"), "<t:1689292800:D>");
+        // Old releases sometimes only have a year, that's shown as is.
+        assert_eq!(discord_date("1969"), "1969");
+        assert_eq!(discord_date("2023-13-45"), "2023-13-45");
+    }
 
-pub async fn read_json<T: Serialize + for<'a> Deserialize<'a>>(
-    file: &mut File,
-) -> Result<T, serde_json::Error> {
-    let mut buffer = String::new();
-    file.read_to_string(&mut buffer).await.unwrap();
+    #[test]
+    fn markdown_is_escaped() {
+        assert_eq!(escape_markdown("cardigan"), "cardigan");
+        assert_eq!(escape_markdown("*NSYNC_[Live]`"), r"\*NSYNC\_\[Live\]\`");
+    }
 
-    serde_json::from_str::<T>(&buffer)
+    #[test]
+    fn masked_links() {
+        assert_eq!(
+            masked_link("cardigan", "https://cider.sh/p"),
+            "[cardigan](https://cider.sh/p)"
+        );
+        assert_eq!(
+            masked_link("Song (Live) [Remastered]", "https://cider.sh/p"),
+            r"[Song \(Live\) \[Remastered\]](https://cider.sh/p)"
+        );
+    }
 }